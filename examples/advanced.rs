@@ -1,17 +1,17 @@
 //! An example showing the basic functionality of `bevy_simple_rich_text`.
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
-use bevy_simple_rich_text::{prelude::*, RichTextSystems, StyleTag};
+use bevy_simple_rich_text::{prelude::*, RichTextSet, StyleTag};
 
 fn main() {
     App::new()
         // Sibling components to `StyleTag` *must* be registered.
         .register_type::<Rainbow>()
-        .add_plugins((DefaultPlugins, RichTextPlugin))
+        .add_plugins((DefaultPlugins, RichTextPlugin::default()))
         .add_systems(Startup, setup)
-        // `TextColor` or `TextFont` modifying systems should run after `RichTextSystems`
-        // to prevent brief flashes of their tagged styles.
-        .add_systems(Update, rainbow_text.after(RichTextSystems))
+        // `TextColor` or `TextFont` modifying systems should run after spans are
+        // rebuilt to prevent brief flashes of their tagged styles.
+        .add_systems(Update, rainbow_text.after(RichTextSet::Rebuild))
         .add_systems(
             Update,
             change_default.run_if(input_just_pressed(KeyCode::Space)),
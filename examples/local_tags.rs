@@ -0,0 +1,41 @@
+//! An example showing how the same tag can have a different style in different
+//! rich text, using `LocalStyleTags`.
+
+use bevy::prelude::*;
+use bevy_simple_rich_text::{prelude::*, StyleTag};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, RichTextPlugin::default()))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    // A global `highlight` tag, used by any rich text that doesn't override it.
+    commands.spawn((
+        StyleTag::new("highlight"),
+        TextColor(Color::srgb(1., 1., 0.)),
+    ));
+
+    // Local style entities don't need a `StyleTag`.
+    let danger = commands.spawn(TextColor(Color::srgb(1., 0.2, 0.2))).id();
+
+    commands
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(20.),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(RichText::new(
+                "This dialog uses the [highlight]global[] highlight.",
+            ));
+            parent.spawn(
+                RichText::new("This dialog uses a [highlight]local[] highlight.")
+                    .with_local_tag("highlight", danger),
+            );
+        });
+}
@@ -5,7 +5,7 @@ use bevy_simple_rich_text::{prelude::*, StyleTag};
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, RichTextPlugin))
+        .add_plugins((DefaultPlugins, RichTextPlugin::default()))
         .add_systems(Startup, setup)
         .run();
 }
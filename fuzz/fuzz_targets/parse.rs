@@ -0,0 +1,40 @@
+#![no_main]
+
+use bevy_simple_rich_text::{
+    escape_markup, parse_richtext, parse_richtext_errors, unescape_markup,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    // Parsing never fails, and always produces at least one section.
+    let sections = parse_richtext(input);
+    assert!(!sections.is_empty());
+
+    let plain = sections
+        .iter()
+        .map(|section| section.value.as_str())
+        .collect::<String>();
+
+    // Text without any markup is rendered as-is.
+    if !input.contains(['[', ']']) {
+        assert_eq!(plain, input);
+    }
+
+    // Reporting errors doesn't change the sections.
+    let (error_sections, _) = parse_richtext_errors(input);
+    assert_eq!(error_sections, sections);
+
+    // Escaped text is rendered exactly, without any tags.
+    let escaped = escape_markup(input);
+    assert_eq!(unescape_markup(&escaped), input);
+
+    let escaped_sections = parse_richtext(&escaped);
+    assert!(escaped_sections.iter().all(|section| section.tags.is_empty()));
+    assert_eq!(
+        escaped_sections
+            .iter()
+            .map(|section| section.value.as_str())
+            .collect::<String>(),
+        input
+    );
+});
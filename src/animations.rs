@@ -0,0 +1,229 @@
+//! Built-in color animations for style tags.
+//!
+//! Add an [`AnimatedTag`] to a [`StyleTag`](crate::StyleTag) entity. Like the
+//! other components of a style, it is cloned onto every span using the tag,
+//! and the span's `TextColor` is updated each frame, based on the color given
+//! to it by its styles.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_simple_rich_text::{animations::AnimatedTag, prelude::*};
+//! fn setup(mut commands: Commands) {
+//!     commands.spawn((
+//!         StyleTag::new("rainbow"),
+//!         TextColor(Color::hsl(0., 0.9, 0.8)),
+//!         AnimatedTag::HueCycle { speed: 180. },
+//!     ));
+//!     commands.spawn(RichText::new("[rainbow]Wow!"));
+//! }
+//! ```
+
+use std::f32::consts::TAU;
+
+use bevy::{
+    color::{Alpha, Color, Hue, Mix},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        system::{Commands, Query, Res},
+    },
+    prelude::ReflectComponent,
+    reflect::Reflect,
+    text::{TextColor, TextSpan},
+    time::Time,
+};
+
+/// A component that animates the `TextColor` of spans styled by a
+/// [`StyleTag`](crate::StyleTag).
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub enum AnimatedTag {
+    /// Cycles the hue of the span's color, starting from its original hue and
+    /// keeping its saturation and lightness.
+    HueCycle {
+        /// The change in hue, in degrees per second.
+        speed: f32,
+    },
+    /// Smoothly fades back and forth between two colors.
+    ColorPulse {
+        /// The color at the start of each period.
+        color_a: Color,
+        /// The color halfway through each period.
+        color_b: Color,
+        /// The duration of a full cycle, in seconds. If this isn't positive,
+        /// the span has `color_a`.
+        period: f32,
+    },
+    /// Makes the span fully transparent for the second half of each period.
+    AlphaBlink {
+        /// The duration of a full cycle, in seconds. If this isn't positive,
+        /// the span is always visible.
+        period: f32,
+    },
+}
+impl AnimatedTag {
+    /// Gets the color at `elapsed` seconds of a span styled with `color`.
+    fn animate(&self, color: Color, elapsed: f32) -> Color {
+        match *self {
+            AnimatedTag::HueCycle { speed } => {
+                color.with_hue((color.hue() + elapsed * speed).rem_euclid(360.))
+            }
+            // Avoid dividing by a period of zero.
+            AnimatedTag::ColorPulse {
+                color_a, period, ..
+            } if period <= 0. => color_a,
+            AnimatedTag::AlphaBlink { period } if period <= 0. => color,
+            AnimatedTag::ColorPulse {
+                color_a,
+                color_b,
+                period,
+            } => {
+                let t = (1. - (elapsed / period * TAU).cos()) / 2.;
+                color_a.mix(&color_b, t)
+            }
+            AnimatedTag::AlphaBlink { period } => {
+                let visible = (elapsed / period).rem_euclid(1.) < 0.5;
+                color.with_alpha(if visible { color.alpha() } else { 0. })
+            }
+        }
+    }
+}
+
+/// The color of an animated span as given by its styles, and the color it was
+/// last animated to.
+///
+/// When the span's color no longer matches `animated`, it has been restyled and
+/// the new color becomes the base color.
+#[derive(Component)]
+pub(crate) struct AnimationBase {
+    color: Color,
+    animated: Color,
+}
+
+pub(crate) fn animate_tags(
+    mut commands: Commands,
+    time: Option<Res<Time>>,
+    mut query: Query<
+        (
+            Entity,
+            &AnimatedTag,
+            &mut TextColor,
+            Option<&mut AnimationBase>,
+        ),
+        With<TextSpan>,
+    >,
+) {
+    let Some(time) = time else {
+        return;
+    };
+    let elapsed = time.elapsed_secs_wrapped();
+
+    for (ent, animation, mut color, base) in &mut query {
+        let base_color = match &base {
+            Some(base) if base.animated == color.0 => base.color,
+            _ => color.0,
+        };
+        let animated = animation.animate(base_color, elapsed);
+        // `TextColor` isn't `PartialEq`, so this is `set_if_neq` by hand.
+        if color.0 != animated {
+            color.0 = animated;
+        }
+
+        let new_base = AnimationBase {
+            color: base_color,
+            animated,
+        };
+        match base {
+            Some(mut base) => *base = new_base,
+            None => {
+                commands.entity(ent).insert(new_base);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_animate() {
+    let red = Color::hsl(0., 0.9, 0.8);
+
+    let hue = AnimatedTag::HueCycle { speed: 90. }.animate(red, 1.);
+    assert_eq!(hue, Color::hsl(90., 0.9, 0.8));
+    let orange = Color::hsl(30., 0.9, 0.8);
+    let hue = AnimatedTag::HueCycle { speed: 90. }.animate(orange, 1.);
+    assert_eq!(hue, Color::hsl(120., 0.9, 0.8));
+
+    let pulse = AnimatedTag::ColorPulse {
+        color_a: Color::BLACK,
+        color_b: Color::WHITE,
+        period: 2.,
+    };
+    assert_eq!(pulse.animate(red, 0.), Color::BLACK);
+    assert_eq!(pulse.animate(red, 1.), Color::WHITE);
+
+    let blink = AnimatedTag::AlphaBlink { period: 1. };
+    assert_eq!(blink.animate(red, 0.25).alpha(), 1.);
+    assert_eq!(blink.animate(red.with_alpha(0.5), 0.25).alpha(), 0.5);
+    assert_eq!(blink.animate(red, 0.75).alpha(), 0.);
+
+    // A period of zero doesn't produce NaN colors.
+    let blink = AnimatedTag::AlphaBlink { period: 0. };
+    assert_eq!(blink.animate(red, 0.75), red);
+    let pulse = AnimatedTag::ColorPulse {
+        color_a: Color::BLACK,
+        color_b: Color::WHITE,
+        period: 0.,
+    };
+    assert_eq!(pulse.animate(red, 1.), Color::BLACK);
+}
+
+#[test]
+fn test_animate_tags() {
+    use bevy::{
+        app::App,
+        ecs::{change_detection::DetectChanges, system::RunSystemOnce},
+        utils::Duration,
+    };
+
+    let mut app = App::new();
+    let mut time = Time::<()>::default();
+    time.advance_by(Duration::from_secs(1));
+    app.insert_resource(time);
+
+    let orange = Color::hsl(30., 0.9, 0.8);
+    let span = app
+        .world_mut()
+        .spawn((
+            TextSpan::new("a"),
+            TextColor(orange),
+            AnimatedTag::HueCycle { speed: 90. },
+        ))
+        .id();
+
+    let last_changed = |app: &App| {
+        app.world()
+            .entity(span)
+            .get_ref::<TextColor>()
+            .unwrap()
+            .last_changed()
+    };
+
+    // The hue is offset from the original hue, rather than accumulating each
+    // frame, and the color isn't changed when the animation is at rest.
+    app.world_mut().run_system_once(animate_tags).unwrap();
+    let changed = last_changed(&app);
+    app.world_mut().run_system_once(animate_tags).unwrap();
+    assert_eq!(
+        app.world().get::<TextColor>(span).unwrap().0,
+        Color::hsl(120., 0.9, 0.8)
+    );
+    assert_eq!(last_changed(&app), changed);
+
+    // Restyling the span changes the base color.
+    app.world_mut().get_mut::<TextColor>(span).unwrap().0 = Color::hsl(0., 0.9, 0.8);
+    app.world_mut().run_system_once(animate_tags).unwrap();
+    assert_eq!(
+        app.world().get::<TextColor>(span).unwrap().0,
+        Color::hsl(90., 0.9, 0.8)
+    );
+}
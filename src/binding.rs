@@ -0,0 +1,117 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use bevy::{
+    app::{App, Plugin},
+    prelude::{
+        Component, DetectChanges, DetectChangesMut, IntoSystemConfigs, Query, Ref, Res, Resource,
+    },
+};
+
+use crate::{RichText, RichText2d, RichTextPlugin, RichTextSchedule, RichTextSystems};
+
+/// A component that keeps the markup of a [`RichText`] or [`RichText2d`] in
+/// sync with the `Resource` `T`.
+///
+/// Whenever `T` changes, the markup is replaced with the result of calling
+/// `formatter`. Any text from `T` that shouldn't be parsed as tags must be
+/// escaped by `formatter`, with [`escape_markup`](crate::escape_markup) or
+/// [`RichTextConfig::escape_markup`](crate::RichTextConfig::escape_markup) for
+/// custom delimiters. Requires [`RichTextPlugin::bind::<T>`](RichTextPlugin::bind) to
+/// be added to the app.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::{RichText, RichTextBinding};
+/// #[derive(Resource)]
+/// struct Score(u32);
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         RichText::new(""),
+///         RichTextBinding::new(|score: &Score| format!("Score: [gold]{}", score.0)),
+///     ));
+/// }
+/// ```
+#[derive(Component)]
+pub struct RichTextBinding<T: Resource> {
+    /// Produces the markup from the `Resource`.
+    pub formatter: Arc<dyn Fn(&T) -> String + Send + Sync>,
+}
+impl<T: Resource> RichTextBinding<T> {
+    /// Creates a new [`RichTextBinding`] with the provided formatter.
+    pub fn new(formatter: impl Fn(&T) -> String + Send + Sync + 'static) -> Self {
+        Self {
+            formatter: Arc::new(formatter),
+        }
+    }
+}
+
+/// A plugin that updates [`RichTextBinding<T>`]s. Created with
+/// [`RichTextPlugin::bind`].
+pub struct RichTextBindingPlugin<T: Resource>(PhantomData<T>);
+impl<T: Resource> Plugin for RichTextBindingPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            RichTextSchedule::get(app),
+            update_richtext_bindings::<T>.before(RichTextSystems),
+        );
+    }
+}
+
+impl RichTextPlugin {
+    /// Creates a plugin that keeps [`RichTextBinding<T>`]s up to date.
+    pub fn bind<T: Resource>() -> RichTextBindingPlugin<T> {
+        RichTextBindingPlugin(PhantomData)
+    }
+}
+
+fn update_richtext_bindings<T: Resource>(
+    resource: Option<Res<T>>,
+    mut rt_query: Query<(&mut RichText, Ref<RichTextBinding<T>>)>,
+    mut rt_2d_query: Query<(&mut RichText2d, Ref<RichTextBinding<T>>)>,
+) {
+    let Some(resource) = resource else {
+        return;
+    };
+
+    let rts = rt_query
+        .iter_mut()
+        .map(|(rt, binding)| (rt.map_unchanged(|rt| &mut rt.0), binding));
+    let rts_2d = rt_2d_query
+        .iter_mut()
+        .map(|(rt, binding)| (rt.map_unchanged(|rt| &mut rt.0), binding));
+
+    for (mut markup, binding) in rts.chain(rts_2d) {
+        if !resource.is_changed() && !binding.is_changed() {
+            continue;
+        }
+
+        let new_markup = (binding.formatter)(&resource);
+        markup.set_if_neq(new_markup);
+    }
+}
+
+#[test]
+fn test_binding() {
+    #[derive(Resource)]
+    struct Score(u32);
+
+    let mut app = App::new();
+    app.add_plugins((RichTextPlugin::default(), RichTextPlugin::bind::<Score>()))
+        .insert_resource(Score(1));
+
+    let ent = app
+        .world_mut()
+        .spawn((
+            RichText::new(""),
+            RichTextBinding::new(|score: &Score| format!("[gold]{}", score.0)),
+        ))
+        .id();
+
+    app.update();
+    assert_eq!(app.world().get::<RichText>(ent).unwrap().0, "[gold]1");
+
+    app.world_mut().resource_mut::<Score>().0 = 2;
+    app.update();
+    assert_eq!(app.world().get::<RichText>(ent).unwrap().0, "[gold]2");
+}
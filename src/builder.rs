@@ -0,0 +1,391 @@
+use std::fmt;
+
+use crate::{
+    escape_markup,
+    parser::{escape_markup_with, Delimiters},
+    RichText, RichText2d, RichTextConfig,
+};
+
+/// Builds markup for a [`RichText`] or [`RichText2d`] piece by piece, escaping
+/// any brackets in the text.
+///
+/// ```
+/// # use bevy_simple_rich_text::RichTextBuilder;
+/// let rich_text = RichTextBuilder::new()
+///     .push_tagged(&["red"], "[Hello]")
+///     .push_text(" world")
+///     .build();
+///
+/// assert_eq!(rich_text.0, "[red][[Hello]][] world");
+/// ```
+///
+/// The builder uses the default `[` and `]` delimiters. Use
+/// [`RichTextBuilder::with_config`] when [`RichTextConfig`] has custom
+/// delimiters.
+#[derive(Debug, Clone)]
+pub struct RichTextBuilder {
+    markup: String,
+    /// The tags currently open at the end of `markup`, or `None` if unknown
+    /// because of [`RichTextBuilder::push_markup`].
+    tags: Option<Vec<String>>,
+    delimiters: Delimiters,
+}
+impl Default for RichTextBuilder {
+    fn default() -> Self {
+        Self {
+            markup: String::new(),
+            tags: Some(vec![]),
+            delimiters: Delimiters::default(),
+        }
+    }
+}
+impl RichTextBuilder {
+    /// Creates a new, empty [`RichTextBuilder`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty [`RichTextBuilder`] that uses the delimiters of
+    /// `config`.
+    ///
+    /// ```
+    /// # use bevy_simple_rich_text::{RichTextBuilder, RichTextConfig};
+    /// let config = RichTextConfig {
+    ///     open_bracket: '<',
+    ///     close_bracket: '>',
+    ///     ..Default::default()
+    /// };
+    /// let rich_text = RichTextBuilder::with_config(&config)
+    ///     .push_tagged(&["red"], "<b>")
+    ///     .build();
+    ///
+    /// assert_eq!(rich_text.0, "<red><<b>>");
+    /// ```
+    pub fn with_config(config: &RichTextConfig) -> Self {
+        Self {
+            delimiters: config.delimiters(),
+            ..Self::default()
+        }
+    }
+
+    /// Appends `text` without any tags.
+    pub fn push_text(self, text: &str) -> Self {
+        self.push_tagged(&[], text)
+    }
+
+    /// Appends `text` with `tags`.
+    ///
+    /// Tags themselves can't be escaped, so they must not contain the
+    /// delimiters or separator, like `[`, `]`, or `,`.
+    pub fn push_tagged(mut self, tags: &[&str], text: &str) -> Self {
+        let d = self.delimiters;
+        if self.tags.as_ref().is_none_or(|current| *current != tags) {
+            self.markup.push(d.open);
+            self.markup.push_str(&tags.join(&d.separator.to_string()));
+            self.markup.push(d.close);
+            self.tags = Some(tags.iter().map(|tag| tag.to_string()).collect());
+        }
+
+        self.markup.push_str(&escape_markup_with(text, d));
+        self
+    }
+
+    /// Appends `markup` as-is, without escaping it.
+    ///
+    /// Any tags opened by `markup` are closed before the next
+    /// [`RichTextBuilder::push_text`] or [`RichTextBuilder::push_tagged`].
+    pub fn push_markup(mut self, markup: &str) -> Self {
+        self.markup.push_str(markup);
+        self.tags = None;
+        self
+    }
+
+    /// Appends a literal opening or closing delimiter, like `[` or `]`, with
+    /// the current tags.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bracket` is not one of the delimiters.
+    pub fn push_literal_bracket(mut self, bracket: char) -> Self {
+        let d = self.delimiters;
+        assert!(
+            bracket == d.open || bracket == d.close,
+            "`{bracket}` is not a bracket"
+        );
+
+        self.markup
+            .push_str(&escape_markup_with(&bracket.to_string(), d));
+        self
+    }
+
+    /// Returns the markup as a [`RichText`].
+    pub fn build(self) -> RichText {
+        RichText(self.markup)
+    }
+
+    /// Returns the markup as a [`RichText2d`].
+    pub fn build_2d(self) -> RichText2d {
+        RichText2d(self.markup)
+    }
+}
+
+/// Creates a [`RichText`] like `format!`, escaping any brackets in the
+/// arguments so that they are displayed literally rather than parsed as tags.
+///
+/// This uses the default `[` and `]` delimiters. With custom delimiters, use
+/// [`RichTextBuilder::with_config`] instead.
+///
+/// ```
+/// # use bevy_simple_rich_text::rich_text;
+/// let name = "[admin] Bob";
+/// let rich_text = rich_text!("[red]Hello {}[]! {:.1}", name, 2.25);
+///
+/// assert_eq!(rich_text.0, "[red]Hello [[admin]] Bob[]! 2.2");
+/// ```
+///
+/// Arguments must be passed explicitly. Variables captured in the format
+/// string, like `{name}`, can't be escaped and are rejected at compile time:
+///
+/// ```compile_fail
+/// # use bevy_simple_rich_text::rich_text;
+/// let name = "[admin] Bob";
+/// let rich_text = rich_text!("[red]Hello {name}");
+/// ```
+#[macro_export]
+macro_rules! rich_text {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {{
+        const _: () = ::std::assert!(
+            !$crate::has_implicit_capture($fmt),
+            "rich_text! can't escape variables captured in the format string. Pass them as arguments instead."
+        );
+        $crate::RichTextBuilder::new()
+            .push_markup(&::std::format!($fmt $(, $crate::Escaped(&$arg))*))
+            .build()
+    }};
+}
+
+/// Returns `true` if the format string `fmt` captures a variable by name, like
+/// `{name}`. Used by [`rich_text!`].
+#[doc(hidden)]
+pub const fn has_implicit_capture(fmt: &str) -> bool {
+    let bytes = fmt.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'{' {
+            if i + 1 < bytes.len() && bytes[i + 1] == b'{' {
+                i += 2;
+                continue;
+            }
+            if i + 1 < bytes.len() && (bytes[i + 1].is_ascii_alphabetic() || bytes[i + 1] == b'_') {
+                return true;
+            }
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Formats a value, escaping any brackets in the output. Used by
+/// [`rich_text!`].
+///
+/// Format specs like `{:.2}` and `{:>5}` are applied to the value before it is
+/// escaped, so padding is based on the displayed width.
+#[doc(hidden)]
+pub struct Escaped<T>(pub T);
+
+/// Formats `value` into a `String` with the flags, width and precision of `f`,
+/// using the formatting trait selected by `$ty` (`""` or `"?"`).
+macro_rules! format_with_spec {
+    ($value:expr, $f:expr, $width:expr, $ty:literal) => {{
+        macro_rules! with_flags {
+            ($flags:literal) => {
+                match ($f.precision(), $width) {
+                    (None, None) => format!(concat!("{:", $flags, $ty, "}"), $value),
+                    (Some(p), None) => {
+                        format!(concat!("{:", $flags, ".p$", $ty, "}"), $value, p = p)
+                    }
+                    (None, Some(w)) => {
+                        format!(concat!("{:", $flags, "w$", $ty, "}"), $value, w = w)
+                    }
+                    (Some(p), Some(w)) => {
+                        format!(
+                            concat!("{:", $flags, "w$.p$", $ty, "}"),
+                            $value,
+                            w = w,
+                            p = p
+                        )
+                    }
+                }
+            };
+        }
+        match ($f.sign_plus(), $f.alternate(), $f.sign_aware_zero_pad()) {
+            (false, false, false) => with_flags!(""),
+            (true, false, false) => with_flags!("+"),
+            (false, true, false) => with_flags!("#"),
+            (true, true, false) => with_flags!("+#"),
+            (false, false, true) => with_flags!("0"),
+            (true, false, true) => with_flags!("+0"),
+            (false, true, true) => with_flags!("#0"),
+            (true, true, true) => with_flags!("+#0"),
+        }
+    }};
+}
+
+/// Writes `formatted`, which was formatted without any alignment, padded to
+/// the width and alignment of `f` and escaped.
+fn write_escaped(f: &mut fmt::Formatter<'_>, formatted: String, padded: bool) -> fmt::Result {
+    let (before, after) = match (f.align(), f.width(), padded) {
+        (Some(align), Some(width), false) => {
+            let padding = width.saturating_sub(formatted.chars().count());
+            match align {
+                fmt::Alignment::Left => (0, padding),
+                fmt::Alignment::Right => (padding, 0),
+                fmt::Alignment::Center => (padding / 2, padding - padding / 2),
+            }
+        }
+        _ => (0, 0),
+    };
+
+    let fill = escape_markup(&f.fill().to_string());
+    for _ in 0..before {
+        f.write_str(&fill)?;
+    }
+    f.write_str(&escape_markup(&formatted))?;
+    for _ in 0..after {
+        f.write_str(&fill)?;
+    }
+    Ok(())
+}
+
+impl<T: fmt::Display> fmt::Display for Escaped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Without an explicit alignment, the value pads itself with spaces so
+        // that it gets its default alignment.
+        let width = f.width().filter(|_| f.align().is_none());
+        let formatted = format_with_spec!(self.0, f, width, "");
+        write_escaped(f, formatted, width.is_some())
+    }
+}
+impl<T: fmt::Debug> fmt::Debug for Escaped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = f.width().filter(|_| f.align().is_none());
+        let formatted = format_with_spec!(self.0, f, width, "?");
+        write_escaped(f, formatted, width.is_some())
+    }
+}
+
+impl fmt::Display for RichTextBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.markup)
+    }
+}
+
+#[test]
+fn test_builder() {
+    use crate::{parse_richtext, TextSection};
+
+    let rich_text = RichTextBuilder::new()
+        .push_tagged(&["red"], "Hello")
+        .push_text(" world")
+        .push_literal_bracket('[')
+        .push_tagged(&["red", "lg"], "]")
+        .build();
+
+    assert_eq!(rich_text.0, "[red]Hello[] world[[[red,lg]]]");
+    assert_eq!(
+        parse_richtext(&rich_text.0),
+        vec![
+            TextSection {
+                value: "Hello".to_string(),
+                tags: vec!["red".to_string()],
+            },
+            TextSection {
+                value: " world[".to_string(),
+                tags: vec![],
+            },
+            TextSection {
+                value: "]".to_string(),
+                tags: vec!["red".to_string(), "lg".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_builder_with_config() {
+    use crate::parser::parse_richtext_permissive_with;
+
+    let config = RichTextConfig {
+        open_bracket: '{',
+        close_bracket: '}',
+        separator: '|',
+        ..Default::default()
+    };
+    let rich_text = RichTextBuilder::with_config(&config)
+        .push_tagged(&["red", "lg"], "{a}")
+        .push_text("[b]")
+        .push_literal_bracket('}')
+        .build();
+
+    assert_eq!(rich_text.0, "{red|lg}{{a}}{}[b]}}");
+    let sections = parse_richtext_permissive_with(&rich_text.0, config.delimiters());
+    assert_eq!(sections[0].value, "{a}");
+    assert_eq!(sections[0].tags, vec!["red".to_string(), "lg".to_string()]);
+    assert_eq!(sections[1].value, "[b]}");
+    assert!(sections[1].tags.is_empty());
+}
+
+#[test]
+fn test_rich_text_macro() {
+    use crate::parse_richtext;
+
+    let name = "[red]Bob]";
+    let score = 10;
+    let rich_text = rich_text!("[blue]{}[] scored {}!", name, score);
+
+    assert_eq!(rich_text.0, "[blue][[red]]Bob]][] scored 10!");
+    assert_eq!(rich_text.plain_text(), "[red]Bob] scored 10!");
+    assert_eq!(
+        parse_richtext(&rich_text.0)[0].tags,
+        vec!["blue".to_string()]
+    );
+
+    let rich_text = RichTextBuilder::new()
+        .push_markup("[red]a")
+        .push_text("b")
+        .build();
+    assert_eq!(rich_text.0, "[red]a[]b");
+
+    // Untagged text at the start doesn't need an empty tag block.
+    let rich_text = RichTextBuilder::new().push_text("a").build();
+    assert_eq!(rich_text.0, "a");
+}
+
+#[test]
+#[should_panic]
+fn test_push_literal_bracket_panics() {
+    RichTextBuilder::new().push_literal_bracket('a');
+}
+
+#[test]
+fn test_rich_text_macro_format_specs() {
+    let rich_text = rich_text!(
+        "{:.2}|{:>5}|{:-<4}|{:^6}|{:+}|{:05}|{:?}|{0}",
+        1.2345,
+        "[a]",
+        "]",
+        "ab",
+        3,
+        -7,
+        "[x]"
+    );
+
+    assert_eq!(
+        rich_text.plain_text(),
+        "1.23|  [a]|]---|  ab  |+3|-0007|\"[x]\"|1.2345"
+    );
+
+    assert!(has_implicit_capture("a {name}"));
+    assert!(has_implicit_capture("{_x:?}"));
+    assert!(!has_implicit_capture("{{name}} {} {0} {:.2}"));
+}
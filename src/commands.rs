@@ -0,0 +1,112 @@
+use bevy::ecs::{
+    entity::Entity,
+    system::{Commands, EntityCommands},
+    world::EntityWorldMut,
+};
+
+use crate::{RichText, RichText2d, StyleTag};
+
+/// An extension trait for [`Commands`] that modifies rich text and registers
+/// style tags.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::prelude::*;
+/// fn update(mut commands: Commands, query: Query<Entity, With<RichText>>) {
+///     for entity in &query {
+///         commands.append_rich_text(entity, "\n[red]Game over");
+///     }
+/// }
+/// ```
+pub trait RichTextCommands {
+    /// Replaces the markup of the [`RichText`] or [`RichText2d`] on `entity`.
+    fn set_rich_text(&mut self, entity: Entity, markup: impl Into<String>);
+    /// Appends `markup` to the [`RichText`] or [`RichText2d`] on `entity`.
+    ///
+    /// No separator is added. See [`RichText::append`].
+    fn append_rich_text(&mut self, entity: Entity, markup: impl Into<String>);
+    /// Registers `entity` as the style for `tag` by inserting a [`StyleTag`].
+    fn register_style(&mut self, tag: impl Into<String>, entity: Entity);
+}
+impl RichTextCommands for Commands<'_, '_> {
+    fn set_rich_text(&mut self, entity: Entity, markup: impl Into<String>) {
+        self.entity(entity).set_rich_text(markup);
+    }
+    fn append_rich_text(&mut self, entity: Entity, markup: impl Into<String>) {
+        self.entity(entity).append_rich_text(markup);
+    }
+    fn register_style(&mut self, tag: impl Into<String>, entity: Entity) {
+        self.entity(entity).insert(StyleTag::new(tag));
+    }
+}
+
+/// An extension trait for [`EntityCommands`] that modifies the entity's rich
+/// text.
+pub trait RichTextEntityCommands {
+    /// Replaces the markup of the entity's [`RichText`] or [`RichText2d`].
+    fn set_rich_text(&mut self, markup: impl Into<String>) -> &mut Self;
+    /// Appends `markup` to the entity's [`RichText`] or [`RichText2d`].
+    ///
+    /// No separator is added. See [`RichText::append`].
+    fn append_rich_text(&mut self, markup: impl Into<String>) -> &mut Self;
+}
+impl RichTextEntityCommands for EntityCommands<'_> {
+    fn set_rich_text(&mut self, markup: impl Into<String>) -> &mut Self {
+        let markup = markup.into();
+        self.queue(move |mut entity: EntityWorldMut| {
+            with_markup(&mut entity, |text| *text = markup);
+        })
+    }
+    fn append_rich_text(&mut self, markup: impl Into<String>) -> &mut Self {
+        let markup = markup.into();
+        self.queue(move |mut entity: EntityWorldMut| {
+            with_markup(&mut entity, |text| text.push_str(&markup));
+        })
+    }
+}
+
+/// Modifies the markup of the entity's [`RichText`] or [`RichText2d`],
+/// marking it as changed.
+fn with_markup(entity: &mut EntityWorldMut, f: impl FnOnce(&mut String)) {
+    if let Some(mut rt) = entity.get_mut::<RichText>() {
+        f(&mut rt.0);
+    } else if let Some(mut rt) = entity.get_mut::<RichText2d>() {
+        f(&mut rt.0);
+    } else {
+        bevy::log::warn!(
+            "Entity {} has no RichText or RichText2d to modify.",
+            entity.id()
+        );
+    }
+}
+
+#[test]
+fn test_rich_text_commands() {
+    use bevy::{app::App, ecs::world::CommandQueue};
+
+    use crate::{RichTextPlugin, StyleTags};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ui = app.world_mut().spawn(RichText::new("a")).id();
+    let text2d = app.world_mut().spawn(RichText2d::new("b")).id();
+    let style = app.world_mut().spawn_empty().id();
+
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, app.world());
+    commands.set_rich_text(ui, "[red]x");
+    commands.append_rich_text(ui, "y");
+    commands
+        .entity(text2d)
+        .append_rich_text("c")
+        .append_rich_text("d");
+    commands.register_style("red", style);
+    queue.apply(app.world_mut());
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<RichText>(ui).unwrap().0, "[red]xy");
+    assert_eq!(world.get::<RichText2d>(text2d).unwrap().0, "bcd");
+    assert_eq!(world.resource::<StyleTags>().get("red"), Some(&style));
+}
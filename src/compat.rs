@@ -0,0 +1,167 @@
+//! Conversions from other rich text formats.
+
+use std::fmt;
+
+use crate::{parser::escape_markup, RichText};
+
+/// An error produced by [`RichText::from_xml`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlParseError {
+    /// A `<` at `position` was never closed by a `>`.
+    UnterminatedTag {
+        /// The byte offset of the `<`.
+        position: usize,
+    },
+    /// An element was opened inside another element, which is not supported.
+    NestedElement {
+        /// The name of the nested element.
+        name: String,
+        /// The byte offset of the nested element.
+        position: usize,
+    },
+    /// A closing tag didn't match the open element.
+    MismatchedClosingTag {
+        /// The name of the open element, if any.
+        expected: Option<String>,
+        /// The name in the closing tag.
+        found: String,
+        /// The byte offset of the closing tag.
+        position: usize,
+    },
+    /// An element was never closed.
+    UnclosedElement {
+        /// The name of the element.
+        name: String,
+    },
+}
+impl fmt::Display for XmlParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnterminatedTag { position } => write!(f, "unterminated tag at {position}"),
+            Self::NestedElement { name, position } => {
+                write!(f, "nested element <{name}> at {position} is not supported")
+            }
+            Self::MismatchedClosingTag {
+                expected: Some(expected),
+                found,
+                position,
+            } => write!(
+                f,
+                "expected </{expected}> but found </{found}> at {position}"
+            ),
+            Self::MismatchedClosingTag {
+                expected: None,
+                found,
+                position,
+            } => write!(f, "unexpected </{found}> at {position}"),
+            Self::UnclosedElement { name } => write!(f, "<{name}> is never closed"),
+        }
+    }
+}
+impl std::error::Error for XmlParseError {}
+
+impl RichText {
+    /// Converts simple XML-like markup into a [`RichText`], so that
+    /// `<em>Hello</em> <strong>World</strong>` becomes `[em]Hello[] [strong]World[]`.
+    ///
+    /// Only a flat structure is supported: elements can't be nested. Attributes
+    /// are dropped with a warning, and the `&lt;`, `&gt;`, `&quot;`, `&apos;`,
+    /// and `&amp;` entities are decoded.
+    ///
+    /// The result uses the default `[` and `]` delimiters.
+    ///
+    /// Requires the `xml_compat` feature.
+    pub fn from_xml(xml: &str) -> Result<RichText, XmlParseError> {
+        let mut markup = String::new();
+        let mut open: Option<String> = None;
+        let mut position = 0;
+
+        while let Some(start) = xml[position..].find('<').map(|i| i + position) {
+            markup.push_str(&escape_markup(&decode_entities(&xml[position..start])));
+
+            let end = xml[start..]
+                .find('>')
+                .map(|i| i + start)
+                .ok_or(XmlParseError::UnterminatedTag { position: start })?;
+            let inner = xml[start + 1..end].trim();
+
+            if let Some(name) = inner.strip_prefix('/') {
+                let name = name.trim();
+                match open.take() {
+                    Some(expected) if expected == name => markup.push_str("[]"),
+                    expected => {
+                        return Err(XmlParseError::MismatchedClosingTag {
+                            expected,
+                            found: name.to_string(),
+                            position: start,
+                        })
+                    }
+                }
+            } else {
+                let (name, attributes) =
+                    inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+
+                if open.is_some() {
+                    return Err(XmlParseError::NestedElement {
+                        name: name.to_string(),
+                        position: start,
+                    });
+                }
+
+                if !attributes.trim().is_empty() {
+                    bevy::log::warn!("Dropping attributes of <{}>: {}", name, attributes.trim());
+                }
+
+                markup.push('[');
+                markup.push_str(name);
+                markup.push(']');
+                open = Some(name.to_string());
+            }
+
+            position = end + 1;
+        }
+
+        markup.push_str(&escape_markup(&decode_entities(&xml[position..])));
+
+        if let Some(name) = open {
+            return Err(XmlParseError::UnclosedElement { name });
+        }
+
+        Ok(RichText(markup))
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[test]
+fn test_from_xml() {
+    assert_eq!(
+        RichText::from_xml("<em>Hello</em> <strong class=\"x\">[World]</strong> &lt;3")
+            .unwrap()
+            .0,
+        "[em]Hello[] [strong][[World]][] <3"
+    );
+
+    assert!(matches!(
+        RichText::from_xml("<em><strong>a</strong></em>"),
+        Err(XmlParseError::NestedElement { .. })
+    ));
+    assert!(matches!(
+        RichText::from_xml("<em>a</strong>"),
+        Err(XmlParseError::MismatchedClosingTag { .. })
+    ));
+    assert!(matches!(
+        RichText::from_xml("<em>a"),
+        Err(XmlParseError::UnclosedElement { .. })
+    ));
+    assert!(matches!(
+        RichText::from_xml("<em"),
+        Err(XmlParseError::UnterminatedTag { .. })
+    ));
+}
@@ -0,0 +1,258 @@
+use std::borrow::Cow;
+
+use bevy::{
+    app::{App, Plugin},
+    log::warn,
+    prelude::{AnyOf, Changed, Entity, Event, EventReader, IntoSystemConfigs, Or, Query, Res},
+    utils::HashSet,
+};
+#[cfg(feature = "diagnostics")]
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::{ResMut, Resource},
+    utils::Duration,
+};
+
+use crate::{
+    parser::{parse_richtext_permissive_with, Delimiters},
+    unknown_tags, InlineStyle, KnownTags, LocalStyleTags, RichText, RichText2d, RichTextConfig,
+    RichTextSchedule, RichTextSystems, StyleTags, TagGroups,
+};
+
+/// An opt-in plugin that helps find stale or missing style tags. Intended for
+/// use during development only.
+///
+/// Send [`LogUnusedStyleTags`] to log the registered [`StyleTag`](crate::StyleTag)s
+/// that are not used by any [`RichText`] or [`RichText2d`], and
+/// [`UndefinedTagsReport`] to log the tags that are used but not registered.
+pub struct RichTextDiagnosticPlugin;
+impl Plugin for RichTextDiagnosticPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LogUnusedStyleTags>();
+        app.add_event::<UndefinedTagsReport>();
+        app.add_systems(
+            RichTextSchedule::get(app),
+            (log_unused_style_tags, log_undefined_tags).after(RichTextSystems),
+        );
+    }
+}
+
+/// An event that causes [`RichTextDiagnosticPlugin`] to log all registered
+/// style tags that are never referenced by any rich text.
+#[derive(Event, Default)]
+pub struct LogUnusedStyleTags;
+
+/// An event that causes [`RichTextDiagnosticPlugin`] to log all tags that are
+/// referenced by rich text but not registered.
+#[derive(Event, Default)]
+pub struct UndefinedTagsReport;
+
+/// A plugin that warns about tags referenced by [`RichText`] that are not
+/// registered, whenever the rich text is rebuilt.
+///
+/// This does nothing in release builds.
+pub struct ValidateRichText;
+impl Plugin for ValidateRichText {
+    fn build(&self, app: &mut App) {
+        if cfg!(debug_assertions) {
+            app.add_systems(
+                RichTextSchedule::get(app),
+                validate_richtext.after(RichTextSystems),
+            );
+        }
+    }
+}
+
+/// The markup of a rich text entity and the styles local to it.
+type RichTextStyles = (
+    Entity,
+    AnyOf<(&'static RichText, &'static RichText2d)>,
+    Option<&'static LocalStyleTags>,
+    Option<&'static InlineStyle>,
+);
+
+/// Rich text whose markup has changed.
+type RichTextChanged = Or<(Changed<RichText>, Changed<RichText2d>)>;
+
+/// Lowercases the tags of `local` if tags are case-insensitive, as expected by
+/// [`KnownTags::contains`].
+fn normalized_local<'a>(
+    local: Option<&'a LocalStyleTags>,
+    config: &RichTextConfig,
+) -> Option<Cow<'a, LocalStyleTags>> {
+    let local = local?;
+    Some(match config.case_insensitive_tags {
+        true => Cow::Owned(LocalStyleTags(
+            local
+                .iter()
+                .map(|(tag, style)| (config.normalize_tag(tag), *style))
+                .collect(),
+        )),
+        false => Cow::Borrowed(local),
+    })
+}
+
+/// Gets the tags known to a rich text entity with the given local styles.
+fn known_tags<'a>(
+    registry: &'a StyleTags,
+    groups: &'a TagGroups,
+    config: &RichTextConfig,
+    local: Option<&'a LocalStyleTags>,
+    inline: Option<&'a InlineStyle>,
+) -> KnownTags<'a> {
+    let mut known = KnownTags::new(registry)
+        .with_groups(groups)
+        .case_insensitive(config.case_insensitive_tags);
+    if let Some(local) = local {
+        known = known.with_local_tags(local);
+    }
+    if let Some(inline) = inline {
+        known = known.with_inline_style(inline);
+    }
+    known
+}
+
+fn validate_richtext(
+    registry: Res<StyleTags>,
+    groups: Res<TagGroups>,
+    config: Res<RichTextConfig>,
+    rt_query: Query<RichTextStyles, RichTextChanged>,
+) {
+    for (ent, (rt, rt_2d), local, inline) in &rt_query {
+        let Some(markup) = rt.map(|rt| &rt.0).or(rt_2d.map(|rt| &rt.0)) else {
+            continue;
+        };
+        let local = normalized_local(local, &config);
+        let known = known_tags(&registry, &groups, &config, local.as_deref(), inline);
+
+        let unknown = unknown_tags(markup, config.delimiters(), &known);
+        if !unknown.is_empty() {
+            warn!("Rich text {} uses unregistered tags: {:?}", ent, unknown);
+        }
+    }
+}
+
+fn referenced_tags(
+    rt_query: &Query<&RichText>,
+    rt_2d_query: &Query<&RichText2d>,
+    d: Delimiters,
+) -> HashSet<String> {
+    rt_query
+        .iter()
+        .map(|rt| rt.0.as_str())
+        .chain(rt_2d_query.iter().map(|rt| rt.0.as_str()))
+        .flat_map(|markup| parse_richtext_permissive_with(markup, d))
+        .flat_map(|section| section.tags)
+        .collect()
+}
+
+fn log_unused_style_tags(
+    mut events: EventReader<LogUnusedStyleTags>,
+    registry: Res<StyleTags>,
+    config: Res<RichTextConfig>,
+    rt_query: Query<&RichText>,
+    rt_2d_query: Query<&RichText2d>,
+) {
+    if events.is_empty() {
+        return;
+    }
+    events.clear();
+
+    let used = referenced_tags(&rt_query, &rt_2d_query, config.delimiters());
+
+    let mut unused = registry
+        .keys()
+        .filter(|tag| !tag.is_empty() && !used.contains(*tag))
+        .collect::<Vec<_>>();
+    unused.sort();
+
+    for tag in unused {
+        warn!("StyleTag `{}` is not used by any rich text.", tag);
+    }
+}
+
+fn log_undefined_tags(
+    mut events: EventReader<UndefinedTagsReport>,
+    registry: Res<StyleTags>,
+    groups: Res<TagGroups>,
+    config: Res<RichTextConfig>,
+    rt_query: Query<RichTextStyles>,
+) {
+    if events.is_empty() {
+        return;
+    }
+    events.clear();
+
+    let mut undefined = rt_query
+        .iter()
+        .flat_map(|(_, (rt, rt_2d), local, inline)| {
+            let markup = rt.map(|rt| &rt.0).or(rt_2d.map(|rt| &rt.0))?;
+            let local = normalized_local(local, &config);
+            let known = known_tags(&registry, &groups, &config, local.as_deref(), inline);
+            Some(unknown_tags(markup, config.delimiters(), &known))
+        })
+        .flatten()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    undefined.sort();
+
+    for tag in undefined {
+        warn!("Tag `{}` is used by rich text but not registered.", tag);
+    }
+}
+
+/// A plugin that registers [`Diagnostic`]s measuring the work done by
+/// [`RichTextPlugin`](crate::RichTextPlugin) each frame.
+///
+/// Requires the `diagnostics` feature.
+#[cfg(feature = "diagnostics")]
+pub struct RichTextDiagnosticsPlugin;
+#[cfg(feature = "diagnostics")]
+impl RichTextDiagnosticsPlugin {
+    /// The number of sections spawned this frame.
+    pub const SECTIONS_PROCESSED: DiagnosticPath =
+        DiagnosticPath::const_new("richtext.sections_processed");
+    /// The number of rich text entities rebuilt this frame.
+    pub const ENTITIES_REBUILT: DiagnosticPath =
+        DiagnosticPath::const_new("richtext.entities_rebuilt");
+    /// The total time spent parsing markup this frame, in microseconds.
+    pub const PARSE_TIME_US: DiagnosticPath = DiagnosticPath::const_new("richtext.parse_time_us");
+}
+#[cfg(feature = "diagnostics")]
+impl Plugin for RichTextDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RichTextStats>();
+        app.register_diagnostic(Diagnostic::new(Self::SECTIONS_PROCESSED))
+            .register_diagnostic(Diagnostic::new(Self::ENTITIES_REBUILT))
+            .register_diagnostic(Diagnostic::new(Self::PARSE_TIME_US).with_suffix("us"));
+        app.add_systems(
+            RichTextSchedule::get(app),
+            update_diagnostics.after(RichTextSystems),
+        );
+    }
+}
+
+/// The work done by `richtext_changed` since the diagnostics were last updated.
+#[cfg(feature = "diagnostics")]
+#[derive(Resource, Default)]
+pub(crate) struct RichTextStats {
+    pub(crate) sections_processed: usize,
+    pub(crate) entities_rebuilt: usize,
+    pub(crate) parse_time: Duration,
+}
+
+#[cfg(feature = "diagnostics")]
+fn update_diagnostics(mut diagnostics: Diagnostics, mut stats: ResMut<RichTextStats>) {
+    diagnostics.add_measurement(&RichTextDiagnosticsPlugin::SECTIONS_PROCESSED, || {
+        stats.sections_processed as f64
+    });
+    diagnostics.add_measurement(&RichTextDiagnosticsPlugin::ENTITIES_REBUILT, || {
+        stats.entities_rebuilt as f64
+    });
+    diagnostics.add_measurement(&RichTextDiagnosticsPlugin::PARSE_TIME_US, || {
+        stats.parse_time.as_secs_f64() * 1_000_000.
+    });
+
+    *stats = RichTextStats::default();
+}
@@ -0,0 +1,121 @@
+use similar::{capture_diff_slices, Algorithm};
+
+use crate::{
+    parser::{parse_richtext, to_markup},
+    RichText, TextSection,
+};
+
+/// A single step in transforming one markup string into another, operating on
+/// the markup's sections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DiffOp {
+    /// Keep the next `len` sections.
+    Equal {
+        /// The number of sections to keep.
+        len: usize,
+    },
+    /// Remove the next `len` sections.
+    Delete {
+        /// The number of sections to remove.
+        len: usize,
+    },
+    /// Insert new sections.
+    Insert(Vec<TextSection>),
+}
+
+/// Computes the [`DiffOp`]s that transform the markup `from` into the markup
+/// `to`. Apply them with [`patch`].
+pub fn diff(from: &str, to: &str) -> Vec<DiffOp> {
+    let from = parse_richtext(from);
+    let to = parse_richtext(to);
+
+    let mut ops = vec![];
+    for op in capture_diff_slices(Algorithm::Myers, &from, &to) {
+        match op {
+            similar::DiffOp::Equal { len, .. } => ops.push(DiffOp::Equal { len }),
+            similar::DiffOp::Delete { old_len, .. } => ops.push(DiffOp::Delete { len: old_len }),
+            similar::DiffOp::Insert {
+                new_index, new_len, ..
+            } => ops.push(DiffOp::Insert(to[new_index..new_index + new_len].to_vec())),
+            similar::DiffOp::Replace {
+                old_len,
+                new_index,
+                new_len,
+                ..
+            } => {
+                ops.push(DiffOp::Delete { len: old_len });
+                ops.push(DiffOp::Insert(to[new_index..new_index + new_len].to_vec()));
+            }
+        }
+    }
+
+    ops
+}
+
+/// Computes a compact, human-readable patch that transforms the markup `from`
+/// into the markup `to`.
+///
+/// Each line of the patch is one of `=N` (keep `N` sections), `-N` (remove `N`
+/// sections), or `+markup` (insert the sections in `markup`). Newlines and
+/// backslashes in the inserted markup are escaped as `\n` and `\\`.
+pub fn diff_minimal(from: &str, to: &str) -> String {
+    diff(from, to)
+        .into_iter()
+        .map(|op| match op {
+            DiffOp::Equal { len } => format!("={len}\n"),
+            DiffOp::Delete { len } => format!("-{len}\n"),
+            DiffOp::Insert(sections) => format!(
+                "+{}\n",
+                to_markup(sections)
+                    .replace('\\', "\\\\")
+                    .replace('\n', "\\n")
+            ),
+        })
+        .collect()
+}
+
+/// Applies the [`DiffOp`]s produced by [`diff`] to the markup `base`.
+pub fn patch(base: &str, diff: &[DiffOp]) -> RichText {
+    let mut base = parse_richtext(base).into_iter();
+    let mut sections = vec![];
+
+    for op in diff {
+        match op {
+            DiffOp::Equal { len } => sections.extend(base.by_ref().take(*len)),
+            DiffOp::Delete { len } => {
+                base.by_ref().take(*len).for_each(drop);
+            }
+            DiffOp::Insert(inserted) => sections.extend(inserted.iter().cloned()),
+        }
+    }
+
+    RichText::from_sections(sections)
+}
+
+#[test]
+fn test_diff() {
+    let from = "Hello [red]World[] and [blue]Bevy";
+    let to = "Hello [green]World[] and [blue]Bevy[]!";
+
+    let diff = diff(from, to);
+    assert_eq!(
+        diff,
+        vec![
+            DiffOp::Equal { len: 1 },
+            DiffOp::Delete { len: 1 },
+            DiffOp::Insert(vec![TextSection {
+                value: "World".to_string(),
+                tags: vec!["green".to_string()],
+            }]),
+            DiffOp::Equal { len: 2 },
+            DiffOp::Insert(vec![TextSection {
+                value: "!".to_string(),
+                tags: vec![],
+            }]),
+        ]
+    );
+
+    assert!(RichText::sections_equal(&patch(from, &diff).0, to));
+    assert_eq!(diff_minimal(from, to), "=1\n-1\n+[green]World\n=2\n+!\n");
+}
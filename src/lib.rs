@@ -11,7 +11,7 @@
 //! fn main() {
 //!     App::new()
 //!         .add_plugins(DefaultPlugins)
-//!         .add_plugins(RichTextPlugin)
+//!         .add_plugins(RichTextPlugin::default())
 //!         .add_systems(Startup, setup)
 //!         .run();
 //! }
@@ -25,50 +25,675 @@
 //!     commands.spawn((RichText::new("[red]Text")));
 //! }
 //! ```
+//!
+//! # System ordering
+//!
+//! [`RichText`] is processed by systems in [`RichTextSystems`], which run in
+//! the `Update` schedule by default (see [`RichTextPlugin::new`]). Because
+//! text layout, UI layout (`UiSystem::Layout`), and transform propagation all
+//! happen later in the frame in `PostUpdate`, spans are always spawned before
+//! they are laid out and rich text spawned before then never shows up empty
+//! for a frame. When [`RichTextSystems`] run in `PostUpdate` themselves, they
+//! are ordered before UI text measurement (`UiSystem::Prepare`) and layout,
+//! `Update2dText`, and `TransformSystem::TransformPropagate`. For rich text
+//! spawned later in the frame, see [`RichTextConfig::build_on_insert`].
+//!
+//! Systems that modify the `TextColor` or `TextFont` of spans should run
+//! `.after(RichTextSystems)` so that their changes aren't overwritten when the
+//! spans are rebuilt or restyled.
+//!
+//! For finer control, [`RichTextSystems`] is split into [`RichTextSet::Sync`],
+//! which updates [`StyleTags`], followed by [`RichTextSet::Rebuild`], which
+//! rebuilds the spans.
 
-use std::iter;
+use std::{borrow::Cow, iter, sync::Arc};
 
 use bevy::{
-    app::{Plugin, Update},
+    app::{App, Plugin, PostUpdate, Update},
+    asset::AssetServer,
+    color::{Alpha, Color},
     ecs::{
         component::{Component, ComponentId},
         entity::Entity,
         query::Changed,
+        schedule::{InternedScheduleLabel, ScheduleLabel},
         system::Resource,
-        world::World,
+        world::{EntityWorldMut, World},
     },
-    hierarchy::DespawnRecursiveExt,
+    hierarchy::{Children, DespawnRecursiveExt, Parent},
     prelude::{
-        AppTypeRegistry, BuildChildren, Deref, DerefMut, DetectChanges, DetectChangesMut,
-        FromWorld, IntoSystemConfigs, Mut, Or, Query, ReflectComponent, RemovedComponents, Res,
-        ResMut, SystemSet, Text, Text2d, With,
+        resource_changed, AppTypeRegistry, BuildChildren, Bundle, Commands, Condition, Deref,
+        DerefMut, DetectChanges, DetectChangesMut, Event, FromWorld, IntoSystemConfigs,
+        IntoSystemSetConfigs, Mut, OnInsert, Or, Query, Reflect, ReflectComponent,
+        RemovedComponents, Res, ResMut, SystemSet, Text, Text2d, Trigger, With,
     },
-    text::TextSpan,
-    utils::HashMap,
+    reflect::PartialReflect,
+    text::{Font, TextColor, TextFont, TextSpan, Update2dText},
+    transform::TransformSystem,
+    ui::UiSystem,
+    utils::{HashMap, HashSet},
 };
 
-use parser::parse_richtext;
+pub use binding::{RichTextBinding, RichTextBindingPlugin};
+pub use builder::RichTextBuilder;
+#[doc(hidden)]
+pub use builder::{has_implicit_capture, Escaped};
+pub use commands::{RichTextCommands, RichTextEntityCommands};
+#[cfg(feature = "diagnostics")]
+pub use diagnostic::RichTextDiagnosticsPlugin;
+pub use diagnostic::{
+    LogUnusedStyleTags, RichTextDiagnosticPlugin, UndefinedTagsReport, ValidateRichText,
+};
+#[cfg(feature = "diff")]
+pub use diff::{diff, diff_minimal, patch, DiffOp};
+pub use measure::{RenderedTextSize, RichTextMeasure};
+use parser::{
+    apply_quote_prefixes, collapse_whitespace, escape_markup_with, inline_tag, is_builtin_tag,
+    parse_richtext_permissive_with, parse_richtext_strict_with, Delimiters, InlineTag,
+};
+pub use parser::{
+    escape_markup, parse_richtext, parse_richtext_callback, parse_richtext_errors,
+    parse_richtext_permissive, parse_richtext_strict, parse_richtext_to_plain,
+    process_escape_sequences, to_markup, unescape_markup, ParseError, RichTextScanner, ScanToken,
+    TextSection,
+};
+#[cfg(feature = "style_config")]
+pub use style_config::{
+    StyleTagConfig, StyleTagConfigError, StyleTagConfigLoader, StyleTagConfigPlugin,
+    StyleTagDefinition,
+};
+use typewriter::FullSpanText;
+pub use typewriter::{MaxVisibleChars, SpanCharRange, TypewriterFinished, TypewriterSpeed};
 
 /// Commonly used types for `bevy_simple_rich_text`.
 pub mod prelude {
-    pub use crate::{RichText, RichText2d, RichTextPlugin, StyleTag, StyleTags};
+    pub use crate::{
+        escape_markup, parse_richtext, rich_text, unescape_markup, RichText, RichText2d,
+        RichTextCommands, RichTextEntityCommands, RichTextPlugin, StyleTag, StyleTagBundle,
+        StyleTags, TextSection, WithLocalTag,
+    };
 }
 
+pub mod animations;
+mod binding;
+mod builder;
+mod commands;
+#[cfg(feature = "xml_compat")]
+pub mod compat;
+mod diagnostic;
+#[cfg(feature = "diff")]
+mod diff;
+mod measure;
 mod parser;
+#[cfg(feature = "style_config")]
+mod style_config;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod typewriter;
 
 /// The top-level component for rich text for `bevy_ui`.
-#[derive(Component)]
+///
+/// When multiple tags in a block like `[a,b]` define the same component, the
+/// last one wins by default. See [`RichTextConfig::tag_order`].
+///
+/// A tag block replaces all of the active tags. A closing tag like `[/italic]`
+/// removes only the most recently opened `italic`, so that
+/// `[bold]a [bold,italic]b[/italic] c` is bold, bold and italic, then bold
+/// again. `[/]` and `[]` both remove every active tag.
+///
+/// A tag like `[#ff0000]` colors the text directly, without a registered
+/// [`StyleTag`]. `#rgb`, `#rrggbb`, and `#rrggbbaa` are supported. Similarly,
+/// `[size=24]` sets the font size and `[font=fonts/bold.ttf]` sets the font,
+/// loaded from the `AssetServer`, unless another tag in the block defines a
+/// `TextFont`. The text isn't laid out until the font has loaded.
+/// `[alpha=0.5]` scales the alpha of the text's color, whichever tag sets it.
+///
+/// The built-in `[quote]` tag indents the text that follows it, and
+/// `[quote="source"]` additionally prefixes it with `"source" says: `. Register
+/// a [`StyleTag`] named `quote` to style quoted text.
+///
+/// The spans are only rebuilt when the markup differs from the
+/// [`PreviousMarkup`]. See [`RebuildRichText`] to force a rebuild.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[require(Text)]
 pub struct RichText(pub String);
 impl RichText {
     /// Creates a new [`RichText`] with the provided markup.
+    ///
+    /// Any brackets in `markup` are interpreted as tags. Use
+    /// [`RichText::from_plain`] for untrusted text.
     pub fn new(markup: impl Into<String>) -> Self {
         Self(markup.into())
     }
+
+    /// Creates a new [`RichText`] that displays `text` literally, escaping any
+    /// brackets with [`escape_markup`].
+    ///
+    /// This assumes the default delimiters. See
+    /// [`RichTextConfig::escape_markup`] for custom delimiters.
+    pub fn from_plain(text: &str) -> Self {
+        Self(escape_markup(text))
+    }
+
+    /// Creates a [`RichText`] from a list of sections, using the default
+    /// delimiters.
+    pub fn from_sections(sections: impl IntoIterator<Item = TextSection>) -> Self {
+        Self(to_markup(sections))
+    }
+
+    /// Creates a [`RichText`] from a list of `(tags, text)` lines, separated by
+    /// newlines.
+    pub fn from_lines(lines: impl IntoIterator<Item = (Vec<String>, String)>) -> Self {
+        Self::from_separated(lines, "\n")
+    }
+
+    /// Creates a [`RichText`] from a list of `(tags, text)` paragraphs,
+    /// separated by blank lines.
+    pub fn from_paragraphs(paragraphs: impl IntoIterator<Item = (Vec<String>, String)>) -> Self {
+        Self::from_separated(paragraphs, "\n\n")
+    }
+
+    fn from_separated(
+        parts: impl IntoIterator<Item = (Vec<String>, String)>,
+        separator: &str,
+    ) -> Self {
+        let mut sections: Vec<TextSection> = vec![];
+
+        for (tags, value) in parts {
+            if let Some(last) = sections.last_mut() {
+                last.value.push_str(separator);
+            }
+            sections.push(TextSection { value, tags });
+        }
+
+        Self::from_sections(sections)
+    }
+
+    /// Returns a new [`RichText`] with `[]` prepended to the markup, so that the
+    /// first section explicitly starts with only the default style.
+    pub fn apply_default_tags(&self) -> RichText {
+        RichText::new(format!("[]{}", self.0))
+    }
+
+    /// Returns a new [`RichText`] with every tag transformed by `f`.
+    ///
+    /// If `f` returns `None`, the tag is removed. Otherwise, it is replaced with
+    /// the returned name.
+    pub fn map_tags<F: Fn(&str) -> Option<String>>(&self, f: F) -> RichText {
+        RichText::from_sections(
+            parse_richtext(&self.0)
+                .into_iter()
+                .map(|section| TextSection {
+                    tags: section.tags.iter().filter_map(|tag| f(tag)).collect(),
+                    ..section
+                }),
+        )
+    }
+
+    /// Returns the tags referenced by the markup that are not [`KnownTags`],
+    /// in the order they first appear.
+    ///
+    /// `known` may be just the [`StyleTags`] registry, or a [`KnownTags`] that
+    /// also includes tag groups and the styles local to a rich text entity.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_rich_text::prelude::*;
+    /// # let mut world = World::new();
+    /// # let registry = StyleTags::from_world(&mut world);
+    /// let rt = RichText::new("[quote]a[#ff0000]b[red]c");
+    /// assert_eq!(rt.validate_tags(&registry), vec!["red".to_string()]);
+    /// ```
+    pub fn validate_tags<'a>(&self, known: impl Into<KnownTags<'a>>) -> Vec<String> {
+        unknown_tags(&self.0, Delimiters::default(), &known.into())
+    }
+
+    /// Returns `true` if any section of the markup uses `tag_name`.
+    ///
+    /// This is `O(n)` in the length of the markup.
+    pub fn contains_tag(&self, tag_name: &str) -> bool {
+        parse_richtext(&self.0)
+            .iter()
+            .any(|section| section.tags.iter().any(|tag| tag == tag_name))
+    }
+
+    /// Returns the set of all unique tags used by the markup.
+    ///
+    /// This is `O(n)` in the length of the markup.
+    pub fn active_tags(&self) -> HashSet<String> {
+        parse_richtext(&self.0)
+            .into_iter()
+            .flat_map(|section| section.tags)
+            .collect()
+    }
+
+    /// Returns `true` if any section of the markup uses `tag`. Same as
+    /// [`RichText::contains_tag`].
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.contains_tag(tag)
+    }
+
+    /// Returns the number of distinct tags used by the markup.
+    ///
+    /// This is `O(n)` in the length of the markup.
+    pub fn tag_count(&self) -> usize {
+        self.active_tags().len()
+    }
+
+    /// Returns the section containing the visible character at `char_index`,
+    /// along with the character's offset within that section.
+    ///
+    /// Returns `None` if `char_index` is past the end of the text.
+    pub fn section_at_char(&self, char_index: usize) -> Option<(TextSection, usize)> {
+        let mut start = 0;
+
+        for section in parse_richtext(&self.0) {
+            let len = section.value.chars().count();
+            if char_index < start + len {
+                return Some((section, char_index - start));
+            }
+            start += len;
+        }
+
+        None
+    }
+
+    /// Returns a new [`RichText`] with `transform` applied to the text of every
+    /// section, leaving tags untouched.
+    pub fn apply_transform(&self, transform: TextTransform) -> RichText {
+        let mut word_start = true;
+
+        RichText::from_sections(parse_richtext(&self.0).into_iter().map(|section| {
+            let value = match transform {
+                TextTransform::Uppercase => section.value.to_uppercase(),
+                TextTransform::Lowercase => section.value.to_lowercase(),
+                TextTransform::Capitalize => {
+                    let mut chars = section.value.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect())
+                        .unwrap_or_default()
+                }
+                TextTransform::TitleCase => {
+                    let mut value = String::with_capacity(section.value.len());
+                    for c in section.value.chars() {
+                        if word_start {
+                            value.extend(c.to_uppercase());
+                        } else {
+                            value.push(c);
+                        }
+                        word_start = c.is_whitespace();
+                    }
+                    value
+                }
+            };
+
+            TextSection { value, ..section }
+        }))
+    }
+
+    /// Returns a new [`RichText`] with the positional placeholders `{0}`, `{1}`,
+    /// etc. in the text replaced by the corresponding item in `args`.
+    ///
+    /// Placeholders in tag names are not replaced, and `args` are displayed
+    /// literally, so they can't inject tags. Placeholders without a
+    /// corresponding argument are left unchanged.
+    ///
+    /// ```
+    /// # use bevy_simple_rich_text::RichText;
+    /// let rt = RichText::new("[red]{0}[]: scored [blue]{1}[] points").format(&["[Bob]", "10"]);
+    /// assert_eq!(rt.0, "[red][[Bob]][]: scored [blue]10[] points");
+    /// ```
+    pub fn format(&self, args: &[&str]) -> RichText {
+        RichText::from_sections(parse_richtext(&self.0).into_iter().map(|section| {
+            let mut value = String::with_capacity(section.value.len());
+            let mut rest = section.value.as_str();
+
+            while let Some(start) = rest.find('{') {
+                value.push_str(&rest[..start]);
+                rest = &rest[start..];
+
+                let Some(end) = rest.find('}') else {
+                    break;
+                };
+
+                match rest[1..end].parse::<usize>() {
+                    Ok(index) if index < args.len() => value.push_str(args[index]),
+                    Ok(index) => {
+                        bevy::log::warn!("No argument for placeholder {{{}}}", index);
+                        value.push_str(&rest[..=end]);
+                    }
+                    Err(_) => value.push_str(&rest[..=end]),
+                }
+                rest = &rest[end + 1..];
+            }
+            value.push_str(rest);
+
+            TextSection { value, ..section }
+        }))
+    }
+
+    /// Returns a bundle of this [`RichText`] and [`LocalStyleTags`] in which
+    /// `tag` refers to the style entity `entity`.
+    ///
+    /// Chain more calls with [`WithLocalTag::with_local_tag`].
+    pub fn with_local_tag(
+        self,
+        tag: impl Into<String>,
+        entity: Entity,
+    ) -> (RichText, LocalStyleTags) {
+        (self, LocalStyleTags::default()).with_local_tag(tag, entity)
+    }
+
+    /// Returns the visible text, without any markup and with escaped brackets
+    /// unescaped.
+    pub fn plain_text(&self) -> String {
+        parse_richtext_to_plain(&self.0)
+    }
+
+    /// Appends `markup` to the end of the markup.
+    ///
+    /// No separator is added, so include any whitespace or newlines in
+    /// `markup`. Tags that are open at the end of the existing markup also
+    /// apply to `markup` until it opens its own.
+    pub fn append(&mut self, markup: &str) {
+        self.0.push_str(markup);
+    }
+
+    /// Inserts `markup` at the start of the markup.
+    ///
+    /// No separator is added. Tags that are open at the end of `markup` also
+    /// apply to the existing markup until it opens its own.
+    pub fn prepend(&mut self, markup: &str) {
+        self.0.insert_str(0, markup);
+    }
+
+    /// Replaces the markup. This is equivalent to assigning to `.0`.
+    pub fn set(&mut self, markup: &str) {
+        markup.clone_into(&mut self.0);
+    }
+
+    /// Removes all of the markup.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns the number of visible characters in the rendered text, excluding
+    /// any markup.
+    ///
+    /// This is `O(n)` in the length of the markup.
+    pub fn char_count(&self) -> usize {
+        parse_richtext_to_plain(&self.0).chars().count()
+    }
+
+    /// Returns the length in bytes of the rendered text, excluding any markup.
+    ///
+    /// This is `O(n)` in the length of the markup.
+    pub fn byte_count(&self) -> usize {
+        parse_richtext_to_plain(&self.0).len()
+    }
+
+    /// Returns the length in bytes of the markup string, including tags and
+    /// escapes. This is what matters when storing the markup.
+    ///
+    /// Use `self.0.chars().count()` for the number of characters.
+    pub fn len_markup(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the length in bytes of the visible text, excluding any markup.
+    /// This is usually what you want when limiting user input.
+    ///
+    /// Equivalent to [`RichText::byte_count`]. Use [`RichText::char_count`] for
+    /// the number of characters.
+    pub fn len_plain(&self) -> usize {
+        parse_richtext_to_plain(&self.0).len()
+    }
+
+    /// Returns `true` if the markup strings `a` and `b` produce the same sections,
+    /// even if the markup itself differs.
+    ///
+    /// For example, `"[red][blue]text"` and `"[blue]text"` are equal because the
+    /// `[red]` block has no text.
+    pub fn sections_equal(a: &str, b: &str) -> bool {
+        parse_richtext(a) == parse_richtext(b)
+    }
+
+    /// Returns an iterator over the whitespace-separated words of the rendered
+    /// text, along with the tags that are active for each word.
+    ///
+    /// A word that spans multiple sections is attributed to the tags of the
+    /// section where it starts.
+    pub fn words(&self) -> impl Iterator<Item = (String, Vec<String>)> {
+        let mut words: Vec<(String, Vec<String>)> = vec![];
+        let mut in_word = false;
+
+        for section in parse_richtext(&self.0) {
+            for c in section.value.chars() {
+                if c.is_whitespace() {
+                    in_word = false;
+                    continue;
+                }
+
+                match words.last_mut() {
+                    Some((word, _)) if in_word => word.push(c),
+                    _ => words.push((c.to_string(), section.tags.clone())),
+                }
+                in_word = true;
+            }
+        }
+
+        words.into_iter()
+    }
+
+    /// Returns an iterator over every visible character of the rendered text,
+    /// along with the tags that are active for that character.
+    ///
+    /// This is `O(n)` in the length of the text and allocates for every
+    /// character, so avoid calling it every frame.
+    pub fn char_tags(&self) -> impl Iterator<Item = (char, Vec<String>)> {
+        parse_richtext(&self.0).into_iter().flat_map(|section| {
+            let tags = section.tags;
+            section
+                .value
+                .chars()
+                .map(move |c| (c, tags.clone()))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Returns an iterator over the tokens of the markup, tracking the active
+    /// tags as it goes. Useful for implementing custom renderers.
+    pub fn scan(&self) -> RichTextScanner {
+        RichTextScanner::new(&self.0)
+    }
+
+    /// Parses the markup, calling `callback` for each section without collecting
+    /// them into a `Vec`.
+    pub fn visit_sections<F: FnMut(&TextSection)>(&self, mut callback: F) {
+        parse_richtext_callback(&self.0, |section| callback(&section));
+    }
+
+    /// Converts the markup to a simple HTML string, wrapping each tagged section
+    /// in a `<span>` with the section's tags as its classes.
+    ///
+    /// `"[red]Hello[] World"` becomes `<span class="red">Hello</span> World`.
+    pub fn as_plain_html(&self) -> String {
+        let mut html = String::new();
+
+        self.visit_sections(|section| {
+            let value = section
+                .value
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+
+            if section.tags.is_empty() {
+                html.push_str(&value);
+            } else {
+                let classes = section.tags.join(" ").replace('"', "&quot;");
+                html.push_str(&format!("<span class=\"{classes}\">{value}</span>"));
+            }
+        });
+
+        html
+    }
+
+    /// Creates a [`RichText`] that transitions from the markup `a` to the markup
+    /// `b` as `t` goes from `0.0` to `1.0`.
+    ///
+    /// The text that `a` and `b` have in common at their start and end is kept,
+    /// while the text in between is shown from both, with `a`'s fading out and
+    /// `b`'s fading in using `[alpha=N]` tags. Tags are preserved for every
+    /// character.
+    ///
+    /// ```
+    /// # use bevy_simple_rich_text::RichText;
+    /// let rt = RichText::interpolate("Hello [red]World", "Hello [blue]Bevy", 0.25);
+    /// assert_eq!(rt.0, "Hello [red,alpha=0.75]World[blue,alpha=0.25]Bevy");
+    /// ```
+    pub fn interpolate(a: &str, b: &str, t: f32) -> RichText {
+        let a = RichText::new(a).char_tags().collect::<Vec<_>>();
+        let b = RichText::new(b).char_tags().collect::<Vec<_>>();
+
+        let prefix = a.iter().zip(&b).take_while(|(a, b)| a == b).count();
+        let suffix = a[prefix..]
+            .iter()
+            .rev()
+            .zip(b[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let a_mid = &a[prefix..a.len() - suffix];
+        let b_mid = &b[prefix..b.len() - suffix];
+
+        let t = t.clamp(0., 1.);
+
+        // Fully transparent text is left out, and fully opaque text is left
+        // untagged, so that `t` of `0.0` and `1.0` give back `a` and `b`.
+        let fade = |chars: &[(char, Vec<String>)], alpha: f32| -> Vec<(char, Vec<String>)> {
+            if alpha <= 0. {
+                return vec![];
+            }
+            chars
+                .iter()
+                .map(|(c, tags)| {
+                    let mut tags = tags.clone();
+                    if alpha < 1. {
+                        tags.push(format!("alpha={alpha:.2}"));
+                    }
+                    (*c, tags)
+                })
+                .collect()
+        };
+
+        let mut sections: Vec<TextSection> = vec![];
+        for (c, tags) in a[..prefix]
+            .iter()
+            .cloned()
+            .chain(fade(a_mid, 1. - t))
+            .chain(fade(b_mid, t))
+            .chain(a[a.len() - suffix..].iter().cloned())
+        {
+            match sections.last_mut() {
+                Some(section) if section.tags == tags => section.value.push(c),
+                _ => sections.push(TextSection {
+                    value: c.to_string(),
+                    tags,
+                }),
+            }
+        }
+
+        RichText(to_markup(sections))
+    }
+}
+
+/// A transformation applied to the text of a [`RichText`] by
+/// [`RichText::apply_transform`], similar to CSS's `text-transform`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextTransform {
+    /// Converts all text to uppercase.
+    Uppercase,
+    /// Converts all text to lowercase.
+    Lowercase,
+    /// Uppercases the first character of each section.
+    Capitalize,
+    /// Uppercases the first character of each whitespace-separated word.
+    TitleCase,
+}
+
+/// Spawns an entity for each of `texts`, returning their ids in order.
+///
+/// All of the spawned entities are processed together in a single run of
+/// [`RichTextSystems`], the same frame the commands are applied.
+pub fn spawn_richtext_batch(
+    commands: &mut Commands,
+    texts: impl IntoIterator<Item = RichText>,
+) -> Vec<Entity> {
+    texts
+        .into_iter()
+        .map(|text| commands.spawn((text, RebuildRichText)).id())
+        .collect()
+}
+
+/// A marker component that, when added to a [`RichText`] or [`RichText2d`],
+/// prevents its spans from being rebuilt or restyled.
+///
+/// Any changes made while the text is disabled are applied when this component
+/// is removed.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct RichTextDisabled;
+
+/// A marker component that rebuilds a [`RichText`] or [`RichText2d`] in the
+/// next run of [`RichTextSystems`], even if its markup hasn't changed.
+///
+/// Changing a [`RichText`] without changing its markup, like with
+/// `set_changed`, doesn't rebuild its spans. Insert this component to force a
+/// rebuild instead. It is removed once the rich text has been rebuilt.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct RebuildRichText;
+
+/// Combines multiple [`RichText`]s into one.
+///
+/// Each part keeps its own styling: tags from the end of one part don't carry
+/// over into the next. This relies on the default delimiters, so it doesn't
+/// work with custom [`RichTextConfig`] delimiters.
+pub fn concat(parts: impl IntoIterator<Item = RichText>) -> RichText {
+    concat_with_separator(parts, "")
+}
+
+/// Combines multiple [`RichText`]s into one, inserting `separator` between each
+/// part as unstyled plain text.
+///
+/// Each part keeps its own styling: tags from the end of one part don't carry
+/// over into the next. Like [`concat()`], this uses the default delimiters.
+pub fn concat_with_separator(
+    parts: impl IntoIterator<Item = RichText>,
+    separator: &str,
+) -> RichText {
+    let separator = format!("[]{}", escape_markup(separator));
+
+    let mut markup = String::new();
+    for (i, part) in parts.into_iter().enumerate() {
+        if i > 0 {
+            markup.push_str(&separator);
+        }
+        markup.push_str(&part.0);
+    }
+
+    RichText(markup)
 }
 
 /// The top-level component for rich text in world-space for 2d cameras.
-#[derive(Component)]
+///
+/// Bevy doesn't have a 3d text primitive, so there is no `RichText3d`. To
+/// label objects in a 3d scene, use [`RichText`] on a UI node positioned with
+/// `Camera::world_to_viewport`, or render a [`RichText2d`] to a texture.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[require(Text2d)]
 pub struct RichText2d(pub String);
 impl RichText2d {
@@ -76,14 +701,52 @@ impl RichText2d {
     pub fn new(markup: impl Into<String>) -> Self {
         Self(markup.into())
     }
+
+    /// Returns the visible text, without any markup and with escaped brackets
+    /// unescaped.
+    pub fn plain_text(&self) -> String {
+        parse_richtext_to_plain(&self.0)
+    }
+
+    /// Appends `markup` to the end of the markup.
+    ///
+    /// No separator is added, so include any whitespace or newlines in
+    /// `markup`. Tags that are open at the end of the existing markup also
+    /// apply to `markup` until it opens its own.
+    pub fn append(&mut self, markup: &str) {
+        self.0.push_str(markup);
+    }
+
+    /// Inserts `markup` at the start of the markup.
+    ///
+    /// No separator is added. Tags that are open at the end of `markup` also
+    /// apply to the existing markup until it opens its own.
+    pub fn prepend(&mut self, markup: &str) {
+        self.0.insert_str(0, markup);
+    }
+
+    /// Replaces the markup. This is equivalent to assigning to `.0`.
+    pub fn set(&mut self, markup: &str) {
+        markup.clone_into(&mut self.0);
+    }
+
+    /// Removes all of the markup.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
 }
 
 /// A component marking an entity as a "style tag" that can be referred to
 /// by its inner string defining a [`RichText`].
 ///
-/// Intentionally not `Reflect` so that this doesn't end up on `TextSpan`s when
-/// the style is cloned.
-#[derive(Component)]
+/// If multiple tags used together define the same component, the tag that
+/// appears last in the markup wins by default.
+///
+/// `StyleTag` is never cloned onto `TextSpan`s along with the rest of the
+/// style, and neither are `Parent` or `Children`, so style entities can be
+/// organized in a hierarchy.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct StyleTag(pub String);
 impl StyleTag {
     /// Creates a new `StyleTag` with the provided tag.
@@ -97,6 +760,238 @@ impl Default for StyleTag {
     }
 }
 
+/// A bundle for a [`StyleTag`] that sets both the color and the font of spans.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::prelude::*;
+/// # fn setup(mut commands: Commands) {
+/// commands.spawn(StyleTagBundle::new(
+///     "title",
+///     Color::srgb(1., 0., 0.),
+///     TextFont::from_font_size(40.),
+/// ));
+/// commands.spawn(StyleTagBundle::color("red", Color::srgb(1., 0., 0.)));
+/// commands.spawn(StyleTagBundle::font("big", TextFont::from_font_size(40.)));
+/// # }
+/// ```
+///
+/// Every component of a style tag is applied to spans, replacing the one from
+/// the default style. [`StyleTagBundle::color`] and
+/// [`StyleTagBundle::font`] only include the one component, so that a
+/// color tag doesn't also reset the font of the default style.
+#[derive(Bundle, Default)]
+pub struct StyleTagBundle {
+    /// The tag.
+    pub tag: StyleTag,
+    /// The color of spans using the tag.
+    pub color: TextColor,
+    /// The font of spans using the tag.
+    pub font: TextFont,
+}
+impl StyleTagBundle {
+    /// Creates a new `StyleTagBundle` with the provided tag, color, and font.
+    pub fn new(tag: impl Into<String>, color: impl Into<Color>, font: TextFont) -> Self {
+        Self {
+            tag: StyleTag::new(tag),
+            color: TextColor(color.into()),
+            font,
+        }
+    }
+    /// Returns a bundle for a style tag that only sets the color of spans.
+    pub fn color(tag: impl Into<String>, color: impl Into<Color>) -> (StyleTag, TextColor) {
+        (StyleTag::new(tag), TextColor(color.into()))
+    }
+    /// Returns a bundle for a style tag that only sets the font of spans.
+    pub fn font(tag: impl Into<String>, font: TextFont) -> (StyleTag, TextFont) {
+        (StyleTag::new(tag), font)
+    }
+}
+
+/// A component that registers additional names for a [`StyleTag`].
+///
+/// Each alias refers to the same style as the tag itself, and is removed from
+/// [`StyleTags`] along with the tag when the entity is despawned.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::{Aliases, StyleTag};
+/// # fn setup(mut commands: Commands) {
+/// commands.spawn((
+///     StyleTag::new("bold"),
+///     Aliases(vec!["b".to_string()]),
+///     TextColor(Color::WHITE),
+/// ));
+/// # }
+/// ```
+#[derive(Component, Debug, Clone, Default)]
+pub struct Aliases(pub Vec<String>);
+
+/// A component that restricts a [`StyleTag`] to rich text with a
+/// [`RichTextAcceptsLabels`] containing this label.
+///
+/// For any other rich text, the tag has no effect. Styles without a label
+/// apply to all rich text. The label should be added along with the
+/// [`StyleTag`], as changing it later doesn't restyle existing rich text.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::{RichText, RichTextAcceptsLabels, StyleTag, StyleTagLabel};
+/// # fn setup(mut commands: Commands) {
+/// commands.spawn((
+///     StyleTag::new("glow"),
+///     StyleTagLabel("menu".to_string()),
+///     TextColor(Color::srgb(1., 1., 0.5)),
+/// ));
+/// // Only the first text glows.
+/// commands.spawn((
+///     RichText::new("[glow]Start"),
+///     RichTextAcceptsLabels(vec!["menu".to_string()]),
+/// ));
+/// commands.spawn(RichText::new("[glow]Score"));
+/// # }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct StyleTagLabel(pub String);
+
+/// A component holding the [`StyleTagLabel`]s of the styles that a
+/// [`RichText`] or [`RichText2d`] accepts.
+///
+/// Changing this component rebuilds the rich text.
+#[derive(Component, Debug, Clone, Default)]
+pub struct RichTextAcceptsLabels(pub Vec<String>);
+
+/// A component that defines a tag which applies several other tags at once.
+///
+/// `[header]` with the group below behaves as if `[large,bold,accent]` had
+/// been written. Groups may contain other groups, but only one level deep.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::TagGroup;
+/// # fn setup(mut commands: Commands) {
+/// commands.spawn(TagGroup::new("header", ["large", "bold", "accent"]));
+/// # }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct TagGroup {
+    /// The name of the group, used as a tag in markup.
+    pub name: String,
+    /// The tags applied by the group, in order.
+    pub members: Vec<String>,
+}
+impl TagGroup {
+    /// Creates a new `TagGroup` applying `members` in order.
+    pub fn new(
+        name: impl Into<String>,
+        members: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            members: members.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A `HashMap` containing a mapping of [`TagGroup`] names to the `Entity`s
+/// holding them.
+///
+/// Like [`StyleTags`], names are lowercase when
+/// [`RichTextConfig::case_insensitive_tags`] is set.
+///
+/// This `Resource` is automatically managed by `bevy_simple_rich_text`.
+#[derive(Resource, Deref, DerefMut, Default)]
+pub struct TagGroups(pub HashMap<String, Entity>);
+
+/// The maximum depth of nested [`TagGroup`]s that will be expanded.
+const MAX_TAG_GROUP_DEPTH: usize = 2;
+
+/// A component that controls the order in which a [`StyleTag`] is applied
+/// relative to other tags on the same span.
+///
+/// Tags are applied in ascending order of priority, so when two tags define the
+/// same component, the tag with the higher priority wins regardless of the
+/// order they're written in. Tags with equal priority are applied in the
+/// order given by [`RichTextConfig::tag_order`].
+///
+/// Tags without a `TagPriority` have a priority of `0`, and the default style
+/// always has a priority of `-1000`.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TagPriority(pub i32);
+
+/// The [`TagPriority`] of the default style.
+const DEFAULT_STYLE_PRIORITY: i32 = -1000;
+
+/// A component that, when added to a [`RichText`] or [`RichText2d`], defines a
+/// single style tag for that text only, without spawning a style entity.
+///
+/// The tag takes precedence over [`LocalStyleTags`] and [`StyleTags`] with the
+/// same name. Its components must be registered with the `AppTypeRegistry`.
+///
+/// Unlike a [`StyleTag`] entity, the components are cloned each time the text
+/// is rebuilt or restyled, which is slower than sharing a style entity when
+/// the same style is used by many texts.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::{InlineStyle, RichText};
+/// # fn setup(mut commands: Commands) {
+/// commands.spawn((
+///     RichText::new("[unique]Hello"),
+///     InlineStyle::new("unique").with(TextColor(Color::srgb(1., 0., 0.))),
+/// ));
+/// # }
+/// ```
+#[derive(Component)]
+pub struct InlineStyle {
+    /// The tag that refers to this style in markup.
+    pub tag: String,
+    /// The style components.
+    pub components: Vec<Box<dyn Reflect>>,
+}
+impl InlineStyle {
+    /// Creates a new `InlineStyle` with the provided tag and no components.
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            components: vec![],
+        }
+    }
+    /// Adds a style component.
+    pub fn with(mut self, component: impl Reflect) -> Self {
+        self.components.push(Box::new(component));
+        self
+    }
+}
+
+/// A component that customizes how a [`StyleTag`] is applied to spans.
+///
+/// When present on a style tag entity, the closure is called with each span
+/// that uses the tag instead of cloning the style tag entity's components.
+/// This allows for computed or procedural styles.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_rich_text::{StyleTag, StyleTagApply};
+/// # fn setup(mut commands: Commands) {
+/// commands.spawn((
+///     StyleTag::new("time"),
+///     StyleTagApply::new(|span| {
+///         let hue = span.world().resource::<Time>().elapsed_secs() * 10. % 360.;
+///         span.insert(TextColor(Color::hsl(hue, 0.9, 0.7)));
+///     }),
+/// ));
+/// # }
+/// ```
+#[derive(Component, Clone)]
+pub struct StyleTagApply(pub Arc<dyn Fn(&mut EntityWorldMut) + Send + Sync>);
+impl StyleTagApply {
+    /// Creates a new [`StyleTagApply`] with the provided closure.
+    pub fn new(apply: impl Fn(&mut EntityWorldMut) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(apply))
+    }
+}
+
 /// A `HashMap` containing a mapping of `StyleTag` tags to the
 /// `Entity`s holding their style components.
 ///
@@ -104,17 +999,298 @@ impl Default for StyleTag {
 #[derive(Resource, Deref, DerefMut)]
 pub struct StyleTags(pub HashMap<String, Entity>);
 
+/// Tracks which [`RichText`] entities use each tag, so that only those are
+/// restyled when a tag changes.
+///
+/// Tags added, removed or moved to another entity in [`StyleTags`] are
+/// detected automatically. Other changes to [`StyleTags`], like `set_changed`,
+/// restyle all rich text.
+///
+/// This `Resource` is automatically managed by `bevy_simple_rich_text`.
+#[derive(Resource, Default)]
+pub struct StyleTagUsers {
+    users: HashMap<String, HashSet<Entity>>,
+    changed: HashSet<String>,
+    /// The tags as of the last restyle.
+    snapshot: HashMap<String, Entity>,
+}
+impl StyleTagUsers {
+    /// Returns the rich text entities whose spans used `tag` when they were
+    /// last built.
+    pub fn tag_users(&self, tag: &str) -> impl Iterator<Item = Entity> + '_ {
+        self.users.get(tag).into_iter().flatten().copied()
+    }
+    /// Marks `tag` as changed, so that only the rich text using it is restyled.
+    ///
+    /// This is useful after modifying the components of a style entity.
+    pub fn mark_changed(&mut self, tag: impl Into<String>) {
+        self.changed.insert(tag.into());
+    }
+    /// Records that `ent` used `tags` when its spans were last built.
+    fn set_tag_users(&mut self, ent: Entity, tags: &HashSet<String>) {
+        self.remove_user(ent);
+        for tag in tags {
+            self.users.entry(tag.clone()).or_default().insert(ent);
+        }
+    }
+    fn remove_user(&mut self, ent: Entity) {
+        self.users.retain(|_, users| {
+            users.remove(&ent);
+            !users.is_empty()
+        });
+    }
+    /// Gets the rich text entities using any of the tags changed since the
+    /// last restyle, or `None` if all rich text should be restyled.
+    fn changed_users(&self, registry: &StyleTags) -> Option<HashSet<Entity>> {
+        let moved = registry
+            .0
+            .iter()
+            .filter(|(tag, ent)| self.snapshot.get(*tag) != Some(*ent))
+            .map(|(tag, _)| tag)
+            .chain(
+                self.snapshot
+                    .keys()
+                    .filter(|tag| !registry.0.contains_key(*tag)),
+            );
+        let changed = self.changed.iter().chain(moved).collect::<HashSet<_>>();
+        if changed.is_empty() || changed.contains(&String::new()) {
+            return None;
+        }
+
+        Some(
+            changed
+                .into_iter()
+                .flat_map(|tag| self.tag_users(tag))
+                .collect(),
+        )
+    }
+    fn clear_changed(&mut self, registry: &StyleTags) {
+        self.changed.clear();
+        self.snapshot.clone_from(&registry.0);
+    }
+}
+
 impl StyleTags {
     /// Gets the `Entity` holding the default style components (the
     /// [`StyleTag`] with the tag `""`.)
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no default style. See [`StyleTags::try_get_default`].
     pub fn get_default(&self) -> &Entity {
         &self.0[""]
     }
+    /// Gets the `Entity` holding the default style components, or `None` if the
+    /// default style has been removed.
+    pub fn try_get_default(&self) -> Option<Entity> {
+        self.0.get("").copied()
+    }
+    /// Spawns a new default style entity if the default style has been removed.
+    pub fn ensure_default(&mut self, world: &mut World) {
+        if self.try_get_default().is_none() {
+            self.0.insert(
+                "".to_string(),
+                world.spawn((DefaultStyle, StyleTag::new(""))).id(),
+            );
+        }
+    }
+    /// Registers a style tag with the style components in `bundle`, reusing the
+    /// existing entity if `tag` is already registered.
+    ///
+    /// When reusing an entity, its existing style components are removed before
+    /// `bundle` is inserted. This is useful when reloading styles, to avoid
+    /// spawning duplicate style tag entities. Like
+    /// [`StyleTags::swap_components`], only reflected components are removed,
+    /// so the [`StyleTag`], hierarchy and components like [`Aliases`] and
+    /// [`TagPriority`] are kept.
+    pub fn spawn_or_update(
+        &mut self,
+        commands: &mut Commands,
+        tag: impl Into<String>,
+        bundle: impl Bundle,
+    ) -> Entity {
+        let tag = tag.into();
+
+        let entity = match self.0.get(&tag) {
+            Some(&entity) => {
+                commands.queue(move |world: &mut World| {
+                    for component in style_components(world, entity).into_iter().flatten() {
+                        world.entity_mut(entity).remove_by_id(component);
+                    }
+                });
+                commands
+                    .entity(entity)
+                    .insert(bundle)
+                    .insert_if_new(StyleTag::new(tag.clone()));
+                entity
+            }
+            None => commands.spawn((StyleTag::new(tag.clone()), bundle)).id(),
+        };
+
+        self.0.insert(tag, entity);
+
+        entity
+    }
+
+    /// Removes all tags, including the default style.
+    ///
+    /// Despawned [`StyleTag`] entities are normally removed automatically, but
+    /// this must be called after removing entities in bulk, for example with
+    /// `World::clear_entities`, which doesn't trigger component removal
+    /// detection. Call [`StyleTags::ensure_default`] afterwards to restore the
+    /// default style.
+    pub fn clear_all(&mut self) {
+        self.0.clear();
+    }
     /// Gets the `Entity` holding the style components for `tag`, falling
     /// back to the `Entity` holding the default style components.
     pub fn get_or_default(&self, tag: &str) -> &Entity {
         self.0.get(tag).unwrap_or_else(|| self.get_default())
     }
+    /// Gets the `Entity` holding the style components for `tag`, or `None` if
+    /// `tag` is not registered.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_rich_text::prelude::*;
+    /// # let mut world = World::new();
+    /// # let tags = StyleTags::from_world(&mut world);
+    /// // `get_or_default` falls back to the default style...
+    /// assert_eq!(tags.get_or_default("missing"), tags.get_default());
+    /// // ...but `get_or_none` does not.
+    /// assert_eq!(tags.get_or_none("missing"), None);
+    /// ```
+    pub fn get_or_none(&self, tag: &str) -> Option<Entity> {
+        self.0.get(tag).copied()
+    }
+    /// Returns an iterator over the registered tags and their style entities,
+    /// in arbitrary order.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_rich_text::prelude::*;
+    /// # let mut world = World::new();
+    /// # let tags = StyleTags::from_world(&mut world);
+    /// for (tag, entity) in tags.iter() {
+    ///     println!("`{tag}` is styled by {entity}");
+    /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Entity)> {
+        self.0.iter().map(|(tag, entity)| (tag.as_str(), *entity))
+    }
+    /// Returns the number of registered tags, including the default style.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_rich_text::prelude::*;
+    /// # let mut world = World::new();
+    /// let tags = StyleTags::from_world(&mut world);
+    /// assert_eq!(tags.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    /// Returns `true` if no tags are registered, not even the default style.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_rich_text::prelude::*;
+    /// # let mut world = World::new();
+    /// let mut tags = StyleTags::from_world(&mut world);
+    /// assert!(!tags.is_empty());
+    ///
+    /// tags.clear_all();
+    /// assert!(tags.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Returns `true` if `tag` is registered.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_rich_text::prelude::*;
+    /// # let mut world = World::new();
+    /// let tags = StyleTags::from_world(&mut world);
+    /// assert!(tags.contains(""));
+    /// assert!(!tags.contains("red"));
+    /// ```
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains_key(tag)
+    }
+    /// Replaces the `Entity` holding the default style components, returning
+    /// the previous one.
+    ///
+    /// All [`RichText`] will be restyled with the new default style, so there is
+    /// no need to call `set_changed` manually.
+    ///
+    /// `entity` must have a [`StyleTag`] or it will be removed from the
+    /// registry.
+    pub fn set_default(&mut self, entity: Entity) -> Option<Entity> {
+        self.0.insert("".to_string(), entity)
+    }
+    /// Unregisters `tag`, returning the `Entity` that held its style.
+    ///
+    /// The style entity is not despawned. All [`RichText`] will be restyled,
+    /// and any spans using `tag` will be treated as using an unknown tag.
+    pub fn remove(&mut self, tag: &str) -> Option<Entity> {
+        self.0.remove(tag)
+    }
+    /// Replaces the `Entity` holding the style components for `tag`, returning
+    /// the previous one.
+    ///
+    /// All [`RichText`] will be restyled with the new style. Like
+    /// [`StyleTags::set_default`], `entity` must have a [`StyleTag`] or it will
+    /// be removed from the registry.
+    pub fn swap(&mut self, tag: &str, entity: Entity) -> Option<Entity> {
+        self.0.insert(tag.to_string(), entity)
+    }
+    /// Replaces the style components of the style entity `to` with the style
+    /// components of `from`, which are removed from `from`.
+    ///
+    /// The [`StyleTag`] and hierarchy components of both entities are left in
+    /// place, and only reflected components are moved, like when styles are
+    /// applied to spans. The components are moved when `commands` are applied,
+    /// and all [`RichText`] using tags registered to `to` are restyled.
+    ///
+    /// This is useful for switching between sets of styles, like light and
+    /// dark themes, without changing which entities are registered. Does
+    /// nothing if `from` and `to` are the same entity.
+    pub fn swap_components(&self, commands: &mut Commands, from: Entity, to: Entity) {
+        if from == to {
+            return;
+        }
+
+        let tags = self
+            .0
+            .iter()
+            .filter(|(_, ent)| **ent == to)
+            .map(|(tag, _)| tag.clone())
+            .collect::<Vec<_>>();
+
+        commands.queue(move |world: &mut World| {
+            let mut users = world.resource_mut::<StyleTagUsers>();
+            for tag in tags {
+                users.mark_changed(tag);
+            }
+
+            let (Some(from_components), Some(to_components)) =
+                (style_components(world, from), style_components(world, to))
+            else {
+                return;
+            };
+
+            for component in to_components {
+                world.entity_mut(to).remove_by_id(component);
+            }
+            for component in &from_components {
+                component_clone_via_reflect(world, *component, from, to);
+            }
+            for component in from_components {
+                world.entity_mut(from).remove_by_id(component);
+            }
+        });
+    }
 }
 impl FromWorld for StyleTags {
     fn from_world(world: &mut World) -> Self {
@@ -130,121 +1306,1496 @@ impl FromWorld for StyleTags {
 #[derive(Component)]
 pub struct DefaultStyle;
 
-/// A SystemSet containing the systems that process [`RichText`] and manage
-/// [`StyleRegistry`].
-#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RichTextSystems;
+/// A component that, when added to a [`RichText`] or [`RichText2d`], overrides
+/// the global default style (`""`) for that text only.
+///
+/// The inner `Entity` is a style entity, like those registered in
+/// [`StyleTags`], but it does not need to be registered. Its components are
+/// applied to every span before any tags.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct LocalDefaultStyle(pub Entity);
 
-/// This plugin adds systems and initializes resources required for processing
+/// A marker component that, when added to a [`RichText`] or [`RichText2d`],
+/// prevents the default style from being applied to its spans. Only the
+/// components of the tags used in the markup are applied.
+///
+/// Unknown tags, which normally fall back to the default style, are ignored.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct NoDefaultStyle;
+
+/// A component that, when added to a [`RichText`] or [`RichText2d`], defines
+/// style tags for that text only.
+///
+/// This allows the same tag to mean different things in different parts of the
+/// UI. Each tag is resolved in this order:
+///
+/// 1. Inline tags like `[#ff0000]`, `[size=24]`, `[alpha=0.5]`, and
+///    `[font=fonts/bold.ttf]`.
+/// 2. The text's [`InlineStyle`].
+/// 3. This component.
+/// 4. The global [`StyleTags`].
+/// 5. Otherwise, the tag is unknown. See [`RichTextConfig::unknown_tags`].
+///
+/// The default style (`""`) is resolved from [`LocalDefaultStyle`], then this
+/// component, then [`StyleTags`].
+///
+/// Style entities in this map don't need a [`StyleTag`], and entries are not
+/// synced from [`StyleTag`] components. Changing this component rebuilds the
+/// rich text.
+#[derive(Component, Clone, Debug, Default, Deref, DerefMut)]
+pub struct LocalStyleTags(pub HashMap<String, Entity>);
+
+/// Adds local style tags to a `(RichText, LocalStyleTags)` bundle created by
+/// [`RichText::with_local_tag`].
+pub trait WithLocalTag {
+    /// Adds a tag to the [`LocalStyleTags`] of this bundle.
+    fn with_local_tag(self, tag: impl Into<String>, entity: Entity) -> (RichText, LocalStyleTags);
+}
+impl WithLocalTag for (RichText, LocalStyleTags) {
+    fn with_local_tag(
+        mut self,
+        tag: impl Into<String>,
+        entity: Entity,
+    ) -> (RichText, LocalStyleTags) {
+        self.1.insert(tag.into(), entity);
+        self
+    }
+}
+
+/// A `HashMap` containing a mapping of localization keys to localized text,
+/// used by the built-in `[loc=key]` tag.
+///
+/// Text following a `[loc=key]` tag is replaced with the localized text for
+/// `key`. Other tags in the same block are still applied, so
+/// `[lg,loc=ui.submit]Submit` displays the `ui.submit` text with the `lg`
+/// style. If `key` has no localized text, the original text is kept.
+///
+/// All [`RichText`] are rebuilt when this `Resource` changes.
+#[derive(Resource, Deref, DerefMut, Default)]
+pub struct LocalizationRegistry(pub HashMap<String, String>);
+impl LocalizationRegistry {
+    fn localize(&self, sections: &mut [TextSection]) {
+        for section in sections {
+            let Some(index) = section.tags.iter().position(|tag| tag.starts_with("loc=")) else {
+                continue;
+            };
+
+            let tag = section.tags.remove(index);
+            if let Some(localized) = self.0.get(&tag["loc=".len()..]) {
+                section.value.clone_from(localized);
+            }
+        }
+    }
+}
+
+/// An event sent when the markup of a [`RichText`] or [`RichText2d`] changes,
+/// before its spans are rebuilt.
+#[derive(Event, Debug, Clone)]
+pub struct ContentChanged {
+    /// The entity holding the rich text.
+    pub entity: Entity,
+    /// The previous markup, or `None` if the rich text is being built for the
+    /// first time.
+    pub old_markup: Option<String>,
+    /// The new markup.
+    pub new_markup: String,
+}
+
+/// An event sent after the spans of a [`RichText`] or [`RichText2d`] have been
+/// rebuilt and styled.
+#[derive(Event, Debug, Clone)]
+pub struct RichTextBuilt {
+    /// The entity holding the rich text.
+    pub entity: Entity,
+}
+
+/// A component added to each `TextSpan` of a [`RichText`] or [`RichText2d`],
+/// holding the tags that were applied to it in order, starting with `""` for
+/// the default style.
+///
+/// This is read-only. When the rich text is rebuilt and every section has the
+/// same tags as before, the existing spans are updated in place. Otherwise, they
+/// are despawned and replaced.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct SourceTags(pub Vec<String>);
+
+/// A component holding the markup that a [`RichText`] or [`RichText2d`] was
+/// last built with.
+///
+/// When a [`RichText`] is changed but its markup is the same as before, it is
+/// not rebuilt. This is automatically managed by `bevy_simple_rich_text`.
+#[derive(Component, Debug, Clone)]
+pub struct PreviousMarkup(pub String);
+
+/// A component holding the number of sections that the markup of a
+/// [`RichText`] or [`RichText2d`] was parsed into when it was last built.
+///
+/// This is automatically managed by `bevy_simple_rich_text`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSectionCount(pub usize);
+
+/// A component holding the unknown tags that a rich text entity has already
+/// been warned about, so that rebuilding it doesn't warn again.
+#[derive(Component, Default)]
+struct WarnedUnknownTags(HashSet<String>);
+
+/// Configuration for `bevy_simple_rich_text`.
+///
+/// This `Resource` is initialized by [`RichTextPlugin`] and may be modified at
+/// any time.
+#[derive(Resource, Debug, Clone)]
+pub struct RichTextConfig {
+    /// When `true`, markup that can't be fully parsed is logged as an error and
+    /// its [`RichText`] is not rebuilt. When `false`, any malformed markup is
+    /// silently ignored.
+    ///
+    /// Enabling this in development builds can help catch typos in markup.
+    pub strict: bool,
+    /// Determines which tag wins when multiple tags in a block like `[a,b]`
+    /// define the same component.
+    pub tag_order: TagApplicationOrder,
+    /// When `true`, runs of whitespace in the rendered text are collapsed into a
+    /// single space and leading and trailing whitespace is removed, similar to
+    /// HTML.
+    pub collapse_whitespace: bool,
+    /// When `true`, the escape sequences `\n` and `\\` in markup are converted
+    /// into a newline and a backslash before it is parsed. See
+    /// [`process_escape_sequences`].
+    ///
+    /// This is useful for markup loaded from formats like JSON or TOML, where
+    /// `\n` may have been written literally.
+    pub process_escape_sequences: bool,
+    /// When `true`, tag names are matched case-insensitively, so `[RED]` uses
+    /// the `red` style.
+    ///
+    /// The names of style tags, aliases, tag groups, and local style tags, and
+    /// the tags in markup, are all converted to lowercase before they are
+    /// compared. [`StyleTags`] is keyed by the lowercase names, so this should
+    /// be set before any [`StyleTag`]s are spawned.
+    pub case_insensitive_tags: bool,
+    /// When `true`, [`RichText`] and [`RichText2d`] are built as soon as they
+    /// are inserted, rather than when [`RichTextSystems`] next runs.
+    ///
+    /// This is useful for rich text spawned after [`RichTextSystems`] has run
+    /// in a frame, which would otherwise be empty for a frame. The trade-off
+    /// is that each rich text is built individually as it is inserted, and
+    /// that style tags spawned in the same frame may not be registered yet,
+    /// so spans may briefly use the default style (and log warnings about
+    /// unknown tags) until they are restyled when [`RichTextSystems`] runs.
+    pub build_on_insert: bool,
+    /// When `true`, a warning is logged for every [`StyleTag`] that has no
+    /// components that can be applied to spans.
+    ///
+    /// Defaults to `true` in debug builds and `false` in release builds.
+    pub validate_empty_tags: bool,
+    /// Determines what happens when markup uses a tag that isn't registered.
+    pub unknown_tags: UnknownTagBehavior,
+    /// The character that opens a tag block. Defaults to `[`.
+    ///
+    /// The delimiters only affect how [`RichText`] and [`RichText2d`] are
+    /// rendered. Helper methods like [`RichText::plain_text`] always use the
+    /// default delimiters, as do the helpers that produce markup:
+    /// [`escape_markup`], [`RichText::from_plain`], [`RichText::from_sections`],
+    /// [`concat()`], [`rich_text!`] and `RichText::from_xml`. With custom
+    /// delimiters, their output may render brackets literally, and escaped text
+    /// may still inject tags. Use [`RichTextConfig::escape_markup`] and
+    /// [`RichTextBuilder::with_config`] instead.
+    pub open_bracket: char,
+    /// The character that closes a tag block. Defaults to `]`.
+    pub close_bracket: char,
+    /// The character that separates tags in a tag block, like `|` in
+    /// `[bold|italic]`. Defaults to `,`.
+    pub separator: char,
+    /// When `true`, a `debug` message is logged for every span that is styled,
+    /// listing the tags whose styles were applied to it.
+    ///
+    /// Requires the `debug_logging` feature. Without it, this does nothing.
+    pub debug_log: bool,
+}
+impl RichTextConfig {
+    pub(crate) fn delimiters(&self) -> Delimiters {
+        Delimiters {
+            open: self.open_bracket,
+            close: self.close_bracket,
+            separator: self.separator,
+        }
+    }
+    /// Escapes any delimiters in `text` so that it is displayed literally when
+    /// embedded in markup. Like [`escape_markup`], but with the configured
+    /// delimiters.
+    ///
+    /// ```
+    /// # use bevy_simple_rich_text::RichTextConfig;
+    /// let config = RichTextConfig {
+    ///     open_bracket: '{',
+    ///     close_bracket: '}',
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(config.escape_markup("{red}[a]"), "{{red}}[a]");
+    /// ```
+    pub fn escape_markup(&self, text: &str) -> String {
+        escape_markup_with(text, self.delimiters())
+    }
+    /// Normalizes the case of `tag` if [`RichTextConfig::case_insensitive_tags`]
+    /// is enabled.
+    fn normalize_tag(&self, tag: &str) -> String {
+        match self.case_insensitive_tags {
+            true => tag.to_lowercase(),
+            false => tag.to_string(),
+        }
+    }
+}
+impl Default for RichTextConfig {
+    fn default() -> Self {
+        let delimiters = Delimiters::default();
+
+        Self {
+            strict: false,
+            tag_order: TagApplicationOrder::default(),
+            collapse_whitespace: false,
+            process_escape_sequences: false,
+            case_insensitive_tags: false,
+            build_on_insert: false,
+            validate_empty_tags: cfg!(debug_assertions),
+            unknown_tags: UnknownTagBehavior::default(),
+            open_bracket: delimiters.open,
+            close_bracket: delimiters.close,
+            separator: delimiters.separator,
+            debug_log: false,
+        }
+    }
+}
+
+/// Determines what happens when markup uses a tag that isn't registered in
+/// [`LocalStyleTags`] or [`StyleTags`].
+///
+/// Inline tags like `[#ff0000]` and the built-in `quote` and `loc` tags are
+/// never considered unknown. See [`KnownTags`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownTagBehavior {
+    /// Logs a warning the first time each rich text entity uses the tag, and
+    /// applies the default style in its place.
+    #[default]
+    Warn,
+    /// Logs an error and skips any text that uses the tag.
+    Error,
+    /// Silently ignores the tag.
+    Ignore,
+    /// Silently applies the default style in place of the tag.
+    UseDefault,
+}
+
+/// The tags that markup can use without them being unknown.
+///
+/// Built-in tags, like inline tags such as `#ff0000`, `quote` and `loc=key`,
+/// are always known. Other tags are known if they are registered in
+/// [`StyleTags`], or in any of the sources added with the builder methods.
+///
+/// This is the same check used for [`RichTextConfig::unknown_tags`] when rich
+/// text is built.
+#[derive(Clone, Copy)]
+pub struct KnownTags<'a> {
+    registry: &'a StyleTags,
+    groups: Option<&'a TagGroups>,
+    local: Option<&'a LocalStyleTags>,
+    inline: Option<&'a str>,
+    case_insensitive: bool,
+}
+impl<'a> KnownTags<'a> {
+    /// Creates a new [`KnownTags`] with the tags registered in `registry`.
+    pub fn new(registry: &'a StyleTags) -> Self {
+        Self {
+            registry,
+            groups: None,
+            local: None,
+            inline: None,
+            case_insensitive: false,
+        }
+    }
+    /// Also knows the names of the [`TagGroup`]s in `groups`.
+    pub fn with_groups(mut self, groups: &'a TagGroups) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+    /// Also knows the tags defined by a rich text entity's [`LocalStyleTags`].
+    pub fn with_local_tags(mut self, local: &'a LocalStyleTags) -> Self {
+        self.local = Some(local);
+        self
+    }
+    /// Also knows the tag of a rich text entity's [`InlineStyle`].
+    pub fn with_inline_style(mut self, inline: &'a InlineStyle) -> Self {
+        self.inline = Some(&inline.tag);
+        self
+    }
+    /// Matches tags regardless of case, like
+    /// [`RichTextConfig::case_insensitive_tags`].
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+    /// Returns `true` if `tag` is known.
+    ///
+    /// When matching regardless of case, the keys of the sources are expected
+    /// to be lowercase, as they are in [`StyleTags`] and [`TagGroups`] when
+    /// [`RichTextConfig::case_insensitive_tags`] is set.
+    pub fn contains(&self, tag: &str) -> bool {
+        if is_builtin_tag(tag) {
+            return true;
+        }
+
+        let tag = match self.case_insensitive {
+            true => Cow::Owned(tag.to_lowercase()),
+            false => Cow::Borrowed(tag),
+        };
+        let tag = tag.as_ref();
+
+        self.registry.contains_key(tag)
+            || self.groups.is_some_and(|groups| groups.contains_key(tag))
+            || self.local.is_some_and(|local| local.contains_key(tag))
+            || self
+                .inline
+                .is_some_and(|inline| match self.case_insensitive {
+                    true => inline.chars().flat_map(char::to_lowercase).eq(tag.chars()),
+                    false => inline == tag,
+                })
+    }
+}
+impl<'a> From<&'a StyleTags> for KnownTags<'a> {
+    fn from(registry: &'a StyleTags) -> Self {
+        Self::new(registry)
+    }
+}
+
+/// Returns the tags in `markup` that are not `known`, in the order they first
+/// appear.
+pub(crate) fn unknown_tags(markup: &str, d: Delimiters, known: &KnownTags) -> Vec<String> {
+    let mut unknown: Vec<String> = vec![];
+
+    for section in parse_richtext_permissive_with(markup, d) {
+        for tag in section.tags {
+            if !known.contains(&tag) && !unknown.contains(&tag) {
+                unknown.push(tag);
+            }
+        }
+    }
+
+    unknown
+}
+
+/// Determines which tag wins when multiple tags in a single block define the
+/// same component.
+///
+/// Components that only one of the tags defines are always applied, so
+/// `[red,bold]` results in bold red text with either order. Tags with a
+/// higher [`TagPriority`] are always applied after tags with a lower one,
+/// regardless of this order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagApplicationOrder {
+    /// Tags are applied from left to right, so the last tag wins. `[red,blue]`
+    /// results in blue text.
+    #[default]
+    LeftToRightLatestWins,
+    /// The first tag wins. `[red,blue]` results in red text.
+    LeftToRightEarliestWins,
+}
+
+/// A SystemSet containing the systems that process [`RichText`] and manage
+/// [`StyleTags`].
+///
+/// These systems run in `Update` (or the schedule given to
+/// [`RichTextPlugin::new`]), before Bevy's text and UI layout systems. See
+/// the [crate-level documentation](crate#system-ordering) for details.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RichTextSystems;
+
+/// A `Resource` holding the schedule that [`RichTextSystems`] run in, as given
+/// to [`RichTextPlugin::new`].
+///
+/// This is inserted by [`RichTextPlugin`]. Companion plugins like
+/// [`RichTextBindingPlugin`] add their systems to the same schedule, so they
+/// must be added after [`RichTextPlugin`].
+#[derive(Resource, Debug, Clone, Copy, Deref)]
+pub struct RichTextSchedule(pub InternedScheduleLabel);
+impl RichTextSchedule {
+    /// Gets the schedule that [`RichTextSystems`] run in, or `Update` if
+    /// [`RichTextPlugin`] hasn't been added yet.
+    pub(crate) fn get(app: &App) -> InternedScheduleLabel {
+        app.world()
+            .get_resource::<RichTextSchedule>()
+            .map_or_else(|| Update.intern(), |schedule| schedule.0)
+    }
+}
+
+/// The parts of [`RichTextSystems`], which run in this order.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RichTextSet {
+    /// Syncs [`StyleTags`] with [`StyleTag`] entities and marks any
+    /// [`RichText`] affected by registry or localization changes.
+    Sync,
+    /// Rebuilds the spans of any changed [`RichText`] or [`RichText2d`], and
+    /// restyles existing spans when [`StyleTags`] changes.
+    Rebuild,
+}
+
+/// This plugin adds systems and initializes resources required for processing
 /// [`RichText`].
-pub struct RichTextPlugin;
+///
+/// By default, the systems run in `Update`. Use [`RichTextPlugin::new`] to run
+/// them in another schedule.
+pub struct RichTextPlugin {
+    config: Option<RichTextConfig>,
+    schedule: InternedScheduleLabel,
+}
+impl Default for RichTextPlugin {
+    fn default() -> Self {
+        Self::new(Update)
+    }
+}
+impl RichTextPlugin {
+    /// Creates a [`RichTextPlugin`] that adds its systems to `schedule`
+    /// instead of `Update`.
+    ///
+    /// Note that when using a schedule like `FixedUpdate`, [`RichTextSystems`]
+    /// may not run every frame. Systems that modify [`RichText`] each frame
+    /// (e.g. animations driven by `Time` in `Update`) may need to move to the
+    /// same schedule, and text can be rendered for a frame before its spans
+    /// are rebuilt.
+    ///
+    /// ```
+    /// # use bevy::app::FixedUpdate;
+    /// # use bevy_simple_rich_text::RichTextPlugin;
+    /// let plugin = RichTextPlugin::new(FixedUpdate);
+    /// ```
+    pub fn new(schedule: impl ScheduleLabel + Clone) -> Self {
+        Self {
+            config: None,
+            schedule: schedule.intern(),
+        }
+    }
+
+    /// Creates a [`RichTextPlugin`] that inserts `config` as the
+    /// [`RichTextConfig`] resource.
+    ///
+    /// ```
+    /// # use bevy_simple_rich_text::{RichTextConfig, RichTextPlugin};
+    /// let plugin = RichTextPlugin::with_config(RichTextConfig {
+    ///     open_bracket: '{',
+    ///     close_bracket: '}',
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn with_config(config: RichTextConfig) -> Self {
+        Self {
+            config: Some(config),
+            ..Default::default()
+        }
+    }
+}
 impl Plugin for RichTextPlugin {
-    fn build(&self, app: &mut bevy::prelude::App) {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RichTextSchedule(self.schedule));
         app.init_resource::<StyleTags>();
+        app.init_resource::<StyleTagUsers>();
+        app.init_resource::<TagGroups>();
+        match &self.config {
+            Some(config) => {
+                app.insert_resource(config.clone());
+            }
+            None => {
+                app.init_resource::<RichTextConfig>();
+            }
+        }
+        app.init_resource::<LocalizationRegistry>();
+        app.add_event::<ContentChanged>();
+        app.add_event::<RichTextBuilt>();
+        app.add_event::<TypewriterFinished>();
+        app.add_observer(build_on_insert::<RichText>)
+            .add_observer(build_on_insert::<RichText2d>);
+        app.register_type::<RichText>()
+            .register_type::<RichText2d>()
+            .register_type::<StyleTag>()
+            .register_type::<animations::AnimatedTag>();
+        app.configure_sets(
+            self.schedule,
+            (RichTextSet::Sync, RichTextSet::Rebuild)
+                .chain()
+                .in_set(RichTextSystems),
+        );
+        if self.schedule == PostUpdate.intern() {
+            // UI text is measured in `UiSystem::Prepare`, before `UiSystem::Layout`.
+            app.configure_sets(
+                PostUpdate,
+                RichTextSystems
+                    .before(UiSystem::Prepare)
+                    .before(UiSystem::Layout)
+                    .before(Update2dText)
+                    .before(TransformSystem::TransformPropagate),
+            );
+        }
+        app.add_systems(
+            self.schedule,
+            (
+                sync_registry,
+                prune_tag_users,
+                sync_tag_groups.after(sync_registry),
+                registry_changed.after(sync_tag_groups),
+                validate_style_tags
+                    .after(sync_registry)
+                    .run_if(resource_changed::<StyleTags>),
+                localization_changed.run_if(resource_changed::<LocalizationRegistry>),
+                config_changed.run_if(resource_changed::<RichTextConfig>),
+            )
+                .in_set(RichTextSet::Sync),
+        );
+        app.add_systems(
+            self.schedule,
+            (
+                richtext_style_refresh
+                    .run_if(resource_changed::<StyleTags>.or(resource_changed::<StyleTagUsers>)),
+                richtext_changed,
+            )
+                .chain()
+                .in_set(RichTextSet::Rebuild),
+        );
+        app.add_systems(
+            self.schedule,
+            (typewriter::typewriter_advance, animations::animate_tags).after(RichTextSystems),
+        );
         app.add_systems(
-            Update,
-            (richtext_changed, registry_changed, sync_registry).in_set(RichTextSystems),
+            PostUpdate,
+            measure::measure_richtext
+                .in_set(RichTextMeasure)
+                .after(UiSystem::PostLayout)
+                .after(Update2dText),
         );
     }
 }
 
+/// Style entities whose tag or aliases have changed.
+type StyleTagChanged = Or<(Changed<StyleTag>, Changed<Aliases>)>;
+
 fn sync_registry(
-    changed: Query<(Entity, &StyleTag), Changed<StyleTag>>,
-    all: Query<(), With<StyleTag>>,
+    changed: Query<Entity, StyleTagChanged>,
+    all: Query<(&StyleTag, Option<&Aliases>)>,
     mut removed: RemovedComponents<StyleTag>,
+    mut removed_aliases: RemovedComponents<Aliases>,
     mut registry: ResMut<StyleTags>,
+    mut users: ResMut<StyleTagUsers>,
+    config: Res<RichTextConfig>,
 ) {
     for ent in removed.read() {
-        registry.0.retain(|_, v| *v != ent);
+        registry.retain(|_, v| *v != ent);
+    }
+    for ent in removed_aliases.read() {
+        if let Ok((style, _)) = all.get(ent) {
+            let tag = config.normalize_tag(&style.0);
+            registry.retain(|k, v| *v != ent || *k == tag);
+        }
     }
     if changed.is_empty() {
         return;
     }
-    for (ent, style) in &changed {
-        registry.0.insert(style.0.clone(), ent);
+    for ent in &changed {
+        let Ok((style, aliases)) = all.get(ent) else {
+            continue;
+        };
+        registry.retain(|_, v| *v != ent);
+        for tag in iter::once(&style.0).chain(aliases.iter().flat_map(|aliases| &aliases.0)) {
+            let tag = config.normalize_tag(tag);
+            // The style entity's components may have changed along with its
+            // tag, even if the tag is still registered to the same entity.
+            users.mark_changed(tag.clone());
+            registry.insert(tag, ent);
+        }
+    }
+
+    registry.retain(|_, v| all.contains(*v));
+}
+
+/// Forgets the tags used by rich text that has been despawned.
+fn prune_tag_users(
+    mut removed: RemovedComponents<RichText>,
+    mut removed_2d: RemovedComponents<RichText2d>,
+    mut users: ResMut<StyleTagUsers>,
+) {
+    for ent in removed.read().chain(removed_2d.read()) {
+        users.bypass_change_detection().remove_user(ent);
+    }
+}
+
+fn sync_tag_groups(
+    changed: Query<(Entity, &TagGroup), Changed<TagGroup>>,
+    mut removed: RemovedComponents<TagGroup>,
+    mut groups: ResMut<TagGroups>,
+    mut commands: Commands,
+    rt_query: Query<Entity, RichTextFilter>,
+    config: Res<RichTextConfig>,
+) {
+    let mut groups_changed = false;
+    for ent in removed.read() {
+        groups.0.retain(|_, v| *v != ent);
+        groups_changed = true;
+    }
+    for (ent, group) in &changed {
+        groups.0.retain(|_, v| *v != ent);
+        groups.0.insert(config.normalize_tag(&group.name), ent);
+        groups_changed = true;
     }
 
-    registry.0.retain(|_, v| all.get(*v).is_ok());
+    // Groups are expanded while parsing, so any rich text that might use them
+    // must be rebuilt.
+    if groups_changed {
+        mark_all_changed(&mut commands, &rt_query);
+    }
 }
 
-fn registry_changed(registry: Res<StyleTags>, mut rt_query: Query<Mut<RichText>>) {
-    if !registry.is_changed() {
+fn validate_style_tags(world: &mut World) {
+    if !world.resource::<RichTextConfig>().validate_empty_tags {
         return;
     }
 
-    for mut rt in &mut rt_query {
-        rt.set_changed();
+    let skipped_components = skipped_components(world);
+    let registry = world.resource::<StyleTags>();
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+
+    for (tag, ent) in registry.iter() {
+        if tag.is_empty() {
+            continue;
+        }
+
+        let Ok(style_ent) = world.get_entity(ent) else {
+            continue;
+        };
+
+        let has_style = style_ent.contains::<StyleTagApply>()
+            || style_ent.archetype().components().any(|component_id| {
+                !skipped_components.contains(&Some(component_id))
+                    && world
+                        .components()
+                        .get_info(component_id)
+                        .and_then(|info| info.type_id())
+                        .is_some_and(|type_id| {
+                            type_registry
+                                .get_type_data::<ReflectComponent>(type_id)
+                                .is_some()
+                        })
+            });
+
+        if !has_style {
+            bevy::log::warn!("StyleTag '{}' has no style components", tag);
+        }
     }
 }
 
-fn richtext_changed(world: &mut World) {
-    let mut ents_query =
-        world.query_filtered::<Entity, Or<(Changed<RichText>, Changed<RichText2d>)>>();
+fn localization_changed(mut commands: Commands, rt_query: Query<Entity, RichTextFilter>) {
+    mark_all_changed(&mut commands, &rt_query);
+}
 
-    let ents = ents_query.iter(world).collect::<Vec<_>>();
-    if ents.is_empty() {
+/// Rebuilds all rich text when the [`RichTextConfig`] changes, since options
+/// like the delimiters affect how the markup is parsed.
+fn config_changed(mut commands: Commands, rt_query: Query<Entity, RichTextFilter>) {
+    mark_all_changed(&mut commands, &rt_query);
+}
+
+/// Rebuilds all rich text when the registry changes and sections may have been
+/// skipped because of [`UnknownTagBehavior::Error`]. Otherwise, styles are
+/// refreshed in place by [`richtext_style_refresh`].
+fn registry_changed(
+    registry: Res<StyleTags>,
+    users: Res<StyleTagUsers>,
+    config: Res<RichTextConfig>,
+    mut commands: Commands,
+    rt_query: Query<Entity, RichTextFilter>,
+) {
+    if !(registry.is_changed() || users.is_changed())
+        || config.unknown_tags != UnknownTagBehavior::Error
+    {
         return;
     }
 
-    let mut rt_query = world.query::<&RichText>();
-    let mut rt_2d_query = world.query::<&RichText2d>();
+    match users.changed_users(&registry) {
+        Some(users) => {
+            for ent in users.into_iter().filter(|ent| rt_query.contains(*ent)) {
+                commands.entity(ent).insert(RebuildRichText);
+            }
+        }
+        None => mark_all_changed(&mut commands, &rt_query),
+    }
+}
+
+/// Entities with either a [`RichText`] or a [`RichText2d`].
+type RichTextFilter = Or<(With<RichText>, With<RichText2d>)>;
+
+/// Marks all rich text to be rebuilt, even if its markup hasn't changed.
+fn mark_all_changed(commands: &mut Commands, rt_query: &Query<Entity, RichTextFilter>) {
+    for ent in rt_query {
+        commands.entity(ent).insert(RebuildRichText);
+    }
+}
+
+/// Returns `true` if `ent` was last built with its current markup and doesn't
+/// otherwise need to be rebuilt, even though its [`RichText`] or
+/// [`RichText2d`] was changed.
+fn markup_unchanged(world: &mut World, ent: Entity) -> bool {
+    let mut forced_query = world.query_filtered::<(), Or<(
+        Changed<LocalStyleTags>,
+        Changed<InlineStyle>,
+        Changed<RichTextAcceptsLabels>,
+        With<RebuildRichText>,
+    )>>();
+    if forced_query.get(world, ent).is_ok() {
+        return false;
+    }
+
+    let Some(previous) = world.get::<PreviousMarkup>(ent) else {
+        return false;
+    };
+    let markup = world
+        .get::<RichText>(ent)
+        .map(|rt| &rt.0)
+        .or_else(|| world.get::<RichText2d>(ent).map(|rt| &rt.0));
+
+    markup.is_some_and(|markup| previous.0 == *markup)
+}
+
+/// Re-applies styles to the existing spans of rich text whose markup hasn't
+/// changed, using the tags recorded in each span's [`SourceTags`], rather than
+/// despawning and respawning them.
+///
+/// Each span's `TextColor` and `TextFont` are reset before the styles are
+/// applied. Other components that a style no longer defines are left in place.
+fn richtext_style_refresh(world: &mut World) {
+    let mut rebuild_query = world.query_filtered::<(), Or<(
+        Changed<RichText>,
+        Changed<RichText2d>,
+        Changed<LocalStyleTags>,
+        Changed<InlineStyle>,
+        Changed<RichTextAcceptsLabels>,
+        With<RebuildRichText>,
+    )>>();
+    let mut ents_query = world.query_filtered::<Entity, Or<(With<RichText>, With<RichText2d>)>>();
+
+    let mut disabled_query = world.query_filtered::<(), With<RichTextDisabled>>();
+
+    let users = world
+        .resource::<StyleTagUsers>()
+        .changed_users(world.resource::<StyleTags>());
+    let candidates = ents_query
+        .iter(world)
+        .filter(|ent| users.as_ref().is_none_or(|users| users.contains(ent)))
+        .collect::<Vec<_>>();
+    // Text that is about to be rebuilt will be styled then, unless the rebuild
+    // is skipped because its markup hasn't changed.
+    let mut refreshed = Vec::with_capacity(candidates.len());
+    for ent in candidates {
+        if rebuild_query.get(world, ent).is_err() || markup_unchanged(world, ent) {
+            refreshed.push(ent);
+        }
+    }
+    let (disabled, ents): (Vec<_>, Vec<_>) = refreshed
+        .into_iter()
+        .partition(|ent| disabled_query.get(world, *ent).is_ok());
+    // Disabled text is rebuilt when it is enabled again.
+    for ent in disabled {
+        world.entity_mut(ent).insert(RebuildRichText);
+    }
+    world.resource_scope(|world, mut users: Mut<StyleTagUsers>| {
+        users
+            .bypass_change_detection()
+            .clear_changed(world.resource::<StyleTags>());
+    });
+
+    let Some(default) = world.resource::<StyleTags>().try_get_default() else {
+        return;
+    };
+    let config = world.resource::<RichTextConfig>().clone();
+    let skipped_components = skipped_components(world);
 
     world.resource_scope(|world, registry: Mut<StyleTags>| {
         for ent in ents {
-            world.commands().entity(ent).despawn_descendants();
-            world.flush();
+            let local = local_styles(world, ent, &config);
+            let styler = SpanStyler {
+                registry: &registry,
+                local: &local,
+                default: local.default.unwrap_or(default),
+                unknown_tags: config.unknown_tags,
+                skipped_components,
+                #[cfg(feature = "debug_logging")]
+                debug_log: config.debug_log,
+            };
 
-            let Ok(rt) = rt_query
-                .get(world, ent)
-                .map(|rt| &rt.0)
-                .or_else(|_| rt_2d_query.get(world, ent).map(|rt| &rt.0))
-            else {
+            let spans = world
+                .get::<Children>(ent)
+                .map(|children| {
+                    children
+                        .iter()
+                        .filter_map(|child| {
+                            Some((*child, world.get::<SourceTags>(*child)?.clone()))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            for (span_ent, tags) in spans {
+                world
+                    .entity_mut(span_ent)
+                    .insert((TextColor::default(), TextFont::default()));
+                styler.apply(world, span_ent, &tags.0);
+            }
+        }
+    });
+
+    world.flush();
+}
+
+/// The styles defined on a rich text entity itself.
+struct LocalStyles {
+    tags: LocalStyleTags,
+    default: Option<Entity>,
+    no_default: bool,
+    /// The tag and components of the entity's [`InlineStyle`].
+    inline: Option<(String, Vec<Box<dyn PartialReflect>>)>,
+    /// The entity's [`RichTextAcceptsLabels`].
+    labels: Vec<String>,
+}
+
+/// Gets the components of a style entity that are never applied to spans: the
+/// [`StyleTag`] itself, and the hierarchy components of style entities that
+/// have a parent or children.
+fn skipped_components(world: &World) -> [Option<ComponentId>; 3] {
+    [
+        world.component_id::<StyleTag>(),
+        world.component_id::<Parent>(),
+        world.component_id::<Children>(),
+    ]
+}
+
+/// Gets the reflected components of a style entity that would be applied to
+/// spans, or `None` if the entity doesn't exist.
+fn style_components(world: &World, ent: Entity) -> Option<Vec<ComponentId>> {
+    let skipped_components = skipped_components(world);
+    let registry = world.resource::<AppTypeRegistry>().read();
+
+    let components = world
+        .get_entity(ent)
+        .ok()?
+        .archetype()
+        .components()
+        .filter(|component| !skipped_components.contains(&Some(*component)))
+        .filter(|component| {
+            world
+                .components()
+                .get_info(*component)
+                .and_then(|info| info.type_id())
+                .is_some_and(|type_id| {
+                    registry
+                        .get_type_data::<ReflectComponent>(type_id)
+                        .is_some()
+                })
+        })
+        .collect();
+
+    Some(components)
+}
+
+/// Gets the [`LocalStyleTags`], local default style, [`InlineStyle`], and
+/// whether [`NoDefaultStyle`] is present for a rich text entity.
+fn local_styles(world: &World, ent: Entity, config: &RichTextConfig) -> LocalStyles {
+    let tags = world
+        .get::<LocalStyleTags>(ent)
+        .map(|tags| {
+            LocalStyleTags(
+                tags.iter()
+                    .map(|(tag, style)| (config.normalize_tag(tag), *style))
+                    .collect(),
+            )
+        })
+        .unwrap_or_default();
+    let default = world
+        .get::<LocalDefaultStyle>(ent)
+        .map(|local| local.0)
+        .or_else(|| tags.get("").copied());
+    let no_default = world.entity(ent).contains::<NoDefaultStyle>();
+    let inline = world.get::<InlineStyle>(ent).map(|inline| {
+        let components = inline
+            .components
+            .iter()
+            .map(|component| component.clone_value())
+            .collect();
+        (config.normalize_tag(&inline.tag), components)
+    });
+
+    let labels = world
+        .get::<RichTextAcceptsLabels>(ent)
+        .map(|labels| labels.0.clone())
+        .unwrap_or_default();
+
+    LocalStyles {
+        tags,
+        default,
+        no_default,
+        inline,
+        labels,
+    }
+}
+
+/// Applies the styles of a list of tags to a span.
+struct SpanStyler<'a> {
+    registry: &'a StyleTags,
+    local: &'a LocalStyles,
+    default: Entity,
+    unknown_tags: UnknownTagBehavior,
+    skipped_components: [Option<ComponentId>; 3],
+    #[cfg(feature = "debug_logging")]
+    debug_log: bool,
+}
+impl SpanStyler<'_> {
+    /// Gets the components of the [`InlineStyle`] if it defines `tag`.
+    fn inline_style(&self, tag: &str) -> Option<&[Box<dyn PartialReflect>]> {
+        match &self.local.inline {
+            Some((inline_tag, components)) if inline_tag == tag => Some(components),
+            _ => None,
+        }
+    }
+    /// Gets the [`TagPriority`] of `tag`.
+    fn priority(&self, world: &World, tag: &str) -> i32 {
+        if tag.is_empty() {
+            return DEFAULT_STYLE_PRIORITY;
+        }
+        if self.inline_style(tag).is_some() {
+            return 0;
+        }
+
+        self.local
+            .tags
+            .get(tag)
+            .or_else(|| self.registry.get(tag))
+            .and_then(|style_ent| world.get::<TagPriority>(*style_ent))
+            .map_or(0, |priority| priority.0)
+    }
+    fn apply(&self, world: &mut World, span_ent: Entity, tags: &[String]) {
+        // Inline font sizes and fonts are applied last, unless a registered
+        // style defines a `TextFont`, which takes precedence. Inline alphas
+        // always scale the final color.
+        let mut inline_font_size = None;
+        let mut inline_font = None;
+        let mut inline_alpha = None;
+        let mut styled_font = false;
+
+        let empty_tags = iter::once("");
+        let mut ordered = empty_tags
+            .chain(tags.iter().map(|t| t.as_str()))
+            .collect::<Vec<_>>();
+        ordered.sort_by_key(|tag| self.priority(world, tag));
+
+        #[cfg(feature = "debug_logging")]
+        let mut applied = vec![];
+
+        for tag in ordered {
+            if let Some(inline) = inline_tag(tag) {
+                #[cfg(feature = "debug_logging")]
+                applied.push(tag);
+
+                match inline {
+                    InlineTag::Color(color) => {
+                        world.entity_mut(span_ent).insert(TextColor(color));
+                    }
+                    InlineTag::FontSize(font_size) => inline_font_size = Some(font_size),
+                    InlineTag::Font(path) => inline_font = Some(path),
+                    InlineTag::Alpha(alpha) => inline_alpha = Some(alpha),
+                }
+                continue;
+            }
+
+            if let Some(components) = self.inline_style(tag) {
+                #[cfg(feature = "debug_logging")]
+                applied.push(tag);
+
+                styled_font |= components.iter().any(|component| {
+                    component
+                        .try_as_reflect()
+                        .is_some_and(|component| component.is::<TextFont>())
+                });
+                component_insert_via_reflect(world, components, span_ent);
                 continue;
+            }
+
+            let style_ent = match tag {
+                "" => &self.default,
+                tag => match self.local.tags.get(tag).or_else(|| self.registry.get(tag)) {
+                    Some(style_ent) => style_ent,
+                    None if self.unknown_tags == UnknownTagBehavior::Ignore => continue,
+                    None => &self.default,
+                },
             };
 
-            let parsed = parse_richtext(rt);
+            if self.local.no_default && *style_ent == self.default {
+                continue;
+            }
 
-            for section in parsed {
-                let mut tags = vec!["".to_string()];
-                tags.extend(section.tags);
+            // The style entity may have been despawned since the registry was
+            // last synced, or be a stale local style.
+            if world.get_entity(*style_ent).is_err() {
+                continue;
+            }
+
+            if let Some(label) = world.get::<StyleTagLabel>(*style_ent) {
+                if !self.local.labels.contains(&label.0) {
+                    continue;
+                }
+            }
+
+            #[cfg(feature = "debug_logging")]
+            applied.push(tag);
+
+            if *style_ent != self.default && world.entity(*style_ent).contains::<TextFont>() {
+                styled_font = true;
+            }
+
+            if let Some(apply) = world.get::<StyleTagApply>(*style_ent).cloned() {
+                (apply.0)(&mut world.entity_mut(span_ent));
+                continue;
+            }
 
-                let span_ent = world.spawn(TextSpan::new(section.value.clone())).id();
+            let components = {
+                let style_entt = world.entity(*style_ent);
 
-                world.entity_mut(ent).add_child(span_ent);
+                let archetype = style_entt.archetype();
+                let components = archetype
+                    .components()
+                    .filter(|component| !self.skipped_components.contains(&Some(*component)))
+                    .collect::<Vec<_>>();
+                components
+            };
 
-                let empty_tags = iter::once("");
-                for tag in empty_tags.chain(tags.iter().map(|t| t.as_str())) {
-                    let style_ent = registry.get_or_default(tag);
+            for component in components {
+                component_clone_via_reflect(world, component, *style_ent, span_ent);
+            }
+        }
 
-                    let components = {
-                        let style_entt = world.entity(*style_ent);
+        if let Some(alpha) = inline_alpha {
+            let mut span = world.entity_mut(span_ent);
+            match span.get_mut::<TextColor>() {
+                Some(mut color) => {
+                    let faded = color.0.alpha() * alpha;
+                    color.0.set_alpha(faded);
+                }
+                None => {
+                    span.insert(TextColor(Color::WHITE.with_alpha(alpha)));
+                }
+            }
+        }
 
-                        let archetype = style_entt.archetype();
-                        let components = archetype.components().collect::<Vec<_>>();
-                        components
-                    };
+        if let (Some(font_size), false) = (inline_font_size, styled_font) {
+            let mut span = world.entity_mut(span_ent);
+            match span.get_mut::<TextFont>() {
+                Some(mut font) => font.font_size = font_size,
+                None => {
+                    span.insert(TextFont::from_font_size(font_size));
+                }
+            }
+        }
 
-                    for component in components {
-                        component_clone_via_reflect(world, component, *style_ent, span_ent);
+        if let (Some(path), false) = (inline_font, styled_font) {
+            match world.get_resource::<AssetServer>() {
+                Some(asset_server) => {
+                    let handle = asset_server.load::<Font>(path);
+                    let mut span = world.entity_mut(span_ent);
+                    match span.get_mut::<TextFont>() {
+                        Some(mut font) => font.font = handle,
+                        None => {
+                            span.insert(TextFont::from_font(handle));
+                        }
                     }
                 }
+                None => {
+                    bevy::log::warn!("No AssetServer to load the font `{}` with.", path);
+                }
             }
         }
-    });
+
+        #[cfg(feature = "debug_logging")]
+        if self.debug_log {
+            bevy::log::debug!(
+                "Styled span {} `{}` with tags {:?}",
+                span_ent,
+                world
+                    .get::<TextSpan>(span_ent)
+                    .map_or("", |span| span.0.as_str()),
+                applied
+            );
+        }
+    }
 }
 
-fn component_clone_via_reflect(
-    world: &mut World,
-    component_id: ComponentId,
-    source: Entity,
-    target: Entity,
+fn richtext_changed(world: &mut World) {
+    let mut ents_query = world.query_filtered::<Entity, Or<(
+        Changed<RichText>,
+        Changed<RichText2d>,
+        Changed<LocalStyleTags>,
+        Changed<InlineStyle>,
+        Changed<RichTextAcceptsLabels>,
+        With<RebuildRichText>,
+    )>>();
+
+    let ents = ents_query.iter(world).collect::<Vec<_>>();
+    build_richtext(world, ents);
+}
+
+/// Builds the rich text for [`RichTextConfig::build_on_insert`] as soon as it
+/// is inserted.
+fn build_on_insert<T: Component>(
+    trigger: Trigger<OnInsert, T>,
+    config: Res<RichTextConfig>,
+    mut commands: Commands,
 ) {
-    world.resource_scope::<AppTypeRegistry, ()>(|world, registry| {
-        let registry = registry.read();
+    if config.build_on_insert {
+        let ent = trigger.entity();
+        commands.queue(move |world: &mut World| build_richtext(world, vec![ent]));
+    }
+}
 
-        let component_info = world
-            .components()
-            .get_info(component_id)
+/// Parses the markup of each rich text entity in `ents` and rebuilds its spans.
+fn build_richtext(world: &mut World, ents: Vec<Entity>) {
+    let mut disabled_query = world.query_filtered::<(), With<RichTextDisabled>>();
+
+    let (disabled, ents): (Vec<_>, Vec<_>) = ents
+        .into_iter()
+        .partition(|ent| disabled_query.get(world, *ent).is_ok());
+    // Disabled text is rebuilt when it is enabled again.
+    for ent in disabled {
+        if !world.entity(ent).contains::<RebuildRichText>() {
+            world.entity_mut(ent).insert(RebuildRichText);
+        }
+    }
+    if ents.is_empty() {
+        return;
+    }
+
+    let mut rt_query = world.query::<&RichText>();
+    let mut rt_2d_query = world.query::<&RichText2d>();
+    let config = world.resource::<RichTextConfig>().clone();
+
+    let Some(default) = world.resource::<StyleTags>().try_get_default() else {
+        bevy::log::warn!("No default style is registered. Skipping rich text processing.");
+        return;
+    };
+
+    let mut pending = Vec::with_capacity(ents.len());
+    for ent in ents {
+        if markup_unchanged(world, ent) {
+            continue;
+        }
+
+        let Ok(rt) = rt_query
+            .get(world, ent)
+            .map(|rt| rt.0.clone())
+            .or_else(|_| rt_2d_query.get(world, ent).map(|rt| rt.0.clone()))
+        else {
+            continue;
+        };
+
+        let previous = world.get::<PreviousMarkup>(ent).map(|p| p.0.clone());
+        if previous.as_ref() != Some(&rt) {
+            world.entity_mut(ent).insert(PreviousMarkup(rt.clone()));
+            world.send_event(ContentChanged {
+                entity: ent,
+                old_markup: previous,
+                new_markup: rt.clone(),
+            });
+        }
+
+        #[cfg(feature = "diagnostics")]
+        let parse_start = bevy::utils::Instant::now();
+
+        let markup = match config.process_escape_sequences {
+            true => Cow::Owned(process_escape_sequences(&rt)),
+            false => Cow::Borrowed(rt.as_str()),
+        };
+
+        let mut parsed = if config.strict {
+            match parse_richtext_strict_with(&markup, config.delimiters()) {
+                Ok(parsed) => parsed,
+                Err(error) => {
+                    bevy::log::error!("Failed to parse rich text `{}`: {}", rt, error);
+                    // The failed markup was recorded as the `PreviousMarkup`
+                    // above, so the error is logged once rather than every
+                    // frame until the markup changes.
+                    world.entity_mut(ent).remove::<RebuildRichText>();
+                    continue;
+                }
+            }
+        } else {
+            parse_richtext_permissive_with(&markup, config.delimiters())
+        };
+
+        if let Some(localization) = world.get_resource::<LocalizationRegistry>() {
+            localization.localize(&mut parsed);
+        }
+
+        if config.collapse_whitespace {
+            collapse_whitespace(&mut parsed);
+        }
+
+        if config.case_insensitive_tags {
+            for tag in parsed.iter_mut().flat_map(|section| &mut section.tags) {
+                // Font paths may be case-sensitive, and quote sources are
+                // displayed.
+                if !tag.starts_with("font=") && !tag.starts_with("quote=") {
+                    *tag = tag.to_lowercase();
+                }
+            }
+        }
+
+        apply_quote_prefixes(&mut parsed);
+
+        #[cfg(feature = "diagnostics")]
+        if let Some(mut stats) = world.get_resource_mut::<diagnostic::RichTextStats>() {
+            stats.parse_time += parse_start.elapsed();
+            stats.entities_rebuilt += 1;
+            stats.sections_processed += parsed.len();
+        }
+
+        let local = local_styles(world, ent, &config);
+
+        world
+            .entity_mut(ent)
+            .insert(TextSectionCount(parsed.len()))
+            .remove::<RebuildRichText>();
+
+        pending.push((ent, parsed, local));
+    }
+
+    let skipped_components = skipped_components(world);
+    let built = pending.iter().map(|(ent, ..)| *ent).collect::<Vec<_>>();
+
+    let group_members = world
+        .get_resource::<TagGroups>()
+        .map(|groups| {
+            groups
+                .iter()
+                .filter_map(|(name, ent)| {
+                    let group = world.get::<TagGroup>(*ent)?;
+                    let members = group
+                        .members
+                        .iter()
+                        .map(|member| config.normalize_tag(member))
+                        .collect();
+                    Some((config.normalize_tag(name), members))
+                })
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    world.resource_scope(|world, registry: Mut<StyleTags>| {
+        for (ent, parsed, local) in pending {
+            let mut used = HashSet::new();
+            let styler = SpanStyler {
+                registry: &registry,
+                local: &local,
+                default: local.default.unwrap_or(default),
+                unknown_tags: config.unknown_tags,
+                skipped_components,
+                #[cfg(feature = "debug_logging")]
+                debug_log: config.debug_log,
+            };
+            // Tags have already been normalized and tag groups expanded.
+            let known = KnownTags {
+                local: Some(&local.tags),
+                inline: local.inline.as_ref().map(|(tag, _)| tag.as_str()),
+                ..KnownTags::new(&registry)
+            };
+            let mut warned = world
+                .entity_mut(ent)
+                .take::<WarnedUnknownTags>()
+                .unwrap_or_default();
+            let mut sections = Vec::with_capacity(parsed.len());
+
+            for section in parsed {
+                let mut expanded = Vec::with_capacity(section.tags.len());
+                expand_tag_groups(section.tags, &group_members, 0, &mut expanded);
+
+                let mut tags = vec!["".to_string()];
+                match config.tag_order {
+                    TagApplicationOrder::LeftToRightLatestWins => tags.extend(expanded),
+                    TagApplicationOrder::LeftToRightEarliestWins => {
+                        tags.extend(expanded.into_iter().rev())
+                    }
+                }
+
+                used.extend(tags.iter().cloned());
+
+                let mut unknown = tags.iter().filter(|tag| !known.contains(tag));
+                match config.unknown_tags {
+                    UnknownTagBehavior::Warn => {
+                        for tag in unknown {
+                            if warned.0.insert(tag.clone()) {
+                                bevy::log::warn!("Rich text {} uses unknown tag `{}`.", ent, tag);
+                            }
+                        }
+                    }
+                    UnknownTagBehavior::Error => {
+                        if let Some(tag) = unknown.next() {
+                            bevy::log::error!(
+                                "Rich text {} uses unknown tag `{}`. Skipping `{}`.",
+                                ent,
+                                tag,
+                                section.value
+                            );
+                            continue;
+                        }
+                    }
+                    UnknownTagBehavior::Ignore | UnknownTagBehavior::UseDefault => {}
+                }
+
+                sections.push((section.value, tags));
+            }
+
+            if !warned.0.is_empty() {
+                world.entity_mut(ent).insert(warned);
+            }
+
+            // When only the text changed, update the existing spans in place so
+            // that their entities stay the same.
+            let reused = reusable_spans(world, ent, &sections);
+            if reused.is_none() {
+                world.entity_mut(ent).despawn_descendants();
+            }
+
+            let mut char_offset = 0;
+            for (i, (value, tags)) in sections.into_iter().enumerate() {
+                let char_len = value.chars().count();
+                let range = SpanCharRange {
+                    start: char_offset,
+                    end: char_offset + char_len,
+                };
+                char_offset += char_len;
+
+                let span_ent = match &reused {
+                    Some(spans) => {
+                        let mut span = world.entity_mut(spans[i]);
+                        if span.get::<TextSpan>().is_none_or(|span| span.0 != value) {
+                            span.insert(TextSpan::new(value));
+                        }
+                        span.insert((range, TextColor::default(), TextFont::default()))
+                            .remove::<FullSpanText>();
+                        spans[i]
+                    }
+                    None => {
+                        let span_ent = world
+                            .spawn((TextSpan::new(value), range, SourceTags(tags.clone())))
+                            .id();
+                        world.entity_mut(ent).add_child(span_ent);
+                        span_ent
+                    }
+                };
+
+                styler.apply(world, span_ent, &tags);
+            }
+
+            world
+                .resource_mut::<StyleTagUsers>()
+                .bypass_change_detection()
+                .set_tag_users(ent, &used);
+        }
+    });
+
+    // Apply anything queued by `StyleTagApply` before announcing the new spans.
+    world.flush();
+    world.send_event_batch(built.into_iter().map(|entity| RichTextBuilt { entity }));
+}
+
+/// Gets the existing spans of `ent` if they can be updated in place to display
+/// `sections`, which requires that every child is a span with the same tags as
+/// the corresponding section.
+fn reusable_spans(
+    world: &World,
+    ent: Entity,
+    sections: &[(String, Vec<String>)],
+) -> Option<Vec<Entity>> {
+    let children = world.get::<Children>(ent)?;
+    if children.len() != sections.len() {
+        return None;
+    }
+
+    children
+        .iter()
+        .zip(sections)
+        .map(|(child, (_, tags))| {
+            let source = world.get::<SourceTags>(*child)?;
+            (source.0 == *tags).then_some(*child)
+        })
+        .collect()
+}
+
+/// Replaces any [`TagGroup`] names in `tags` with their members.
+fn expand_tag_groups(
+    tags: impl IntoIterator<Item = String>,
+    groups: &HashMap<String, Vec<String>>,
+    depth: usize,
+    out: &mut Vec<String>,
+) {
+    for tag in tags {
+        match groups.get(&tag) {
+            Some(members) if depth < MAX_TAG_GROUP_DEPTH => {
+                expand_tag_groups(members.iter().cloned(), groups, depth + 1, out);
+            }
+            Some(_) => {
+                bevy::log::warn!(
+                    "Tag group `{}` is nested too deeply and will not be expanded.",
+                    tag
+                );
+            }
+            None => out.push(tag),
+        }
+    }
+}
+
+fn component_insert_via_reflect(
+    world: &mut World,
+    components: &[Box<dyn PartialReflect>],
+    target: Entity,
+) {
+    world.resource_scope::<AppTypeRegistry, ()>(|world, registry| {
+        let registry = registry.read();
+
+        for component in components {
+            let Some(type_info) = component.get_represented_type_info() else {
+                continue;
+            };
+            let Some(reflect_component) =
+                registry.get_type_data::<ReflectComponent>(type_info.type_id())
+            else {
+                bevy::log::warn!(
+                    "InlineStyle component `{}` is not a registered component.",
+                    type_info.type_path()
+                );
+                continue;
+            };
+            let mut target = world
+                .get_entity_mut(target)
+                .expect("Target entity must exist");
+            reflect_component.apply_or_insert(
+                &mut target,
+                component.as_partial_reflect(),
+                &registry,
+            );
+        }
+    });
+}
+
+fn component_clone_via_reflect(
+    world: &mut World,
+    component_id: ComponentId,
+    source: Entity,
+    target: Entity,
+) {
+    world.resource_scope::<AppTypeRegistry, ()>(|world, registry| {
+        let registry = registry.read();
+
+        let component_info = world
+            .components()
+            .get_info(component_id)
             .expect("Component must be registered");
         let Some(type_id) = component_info.type_id() else {
             return;
@@ -262,3 +2813,1600 @@ fn component_clone_via_reflect(
         reflect_component.apply_or_insert(&mut target, &*source_component, &registry);
     });
 }
+
+#[test]
+fn test_words() {
+    let words = RichText::new("[red]Hello  [blue]wor[green]ld[] !")
+        .words()
+        .collect::<Vec<_>>();
+
+    assert_eq!(words.len(), 3);
+    assert_eq!(words[0], ("Hello".to_string(), vec!["red".to_string()]));
+    assert_eq!(words[1], ("world".to_string(), vec!["blue".to_string()]));
+    assert_eq!(words[2], ("!".to_string(), vec![]));
+}
+
+#[test]
+fn test_char_tags() {
+    let chars = RichText::new("a[red]b[[").char_tags().collect::<Vec<_>>();
+
+    assert_eq!(
+        chars,
+        vec![
+            ('a', vec![]),
+            ('b', vec!["red".to_string()]),
+            ('[', vec!["red".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn test_interpolate() {
+    let a = "Hello [red]World";
+    let b = "Hello [blue]Bevy";
+
+    assert_eq!(RichText::interpolate(a, b, 0.).0, a);
+    assert_eq!(
+        RichText::interpolate(a, b, 0.5).0,
+        "Hello [red,alpha=0.50]World[blue,alpha=0.50]Bevy"
+    );
+    assert_eq!(RichText::interpolate(a, b, 1.).0, b);
+}
+
+#[test]
+fn test_tag_order() {
+    use bevy::{
+        app::App,
+        color::palettes::css::{BLUE, RED},
+        prelude::{Children, TextColor},
+    };
+
+    fn span_color(config: RichTextConfig) -> TextColor {
+        let mut app = App::new();
+        app.add_plugins(RichTextPlugin::default())
+            .register_type::<TextColor>()
+            .insert_resource(config);
+
+        app.world_mut()
+            .spawn((StyleTag::new("red"), TextColor(RED.into())));
+        app.world_mut()
+            .spawn((StyleTag::new("blue"), TextColor(BLUE.into())));
+        let ent = app.world_mut().spawn(RichText::new("[red,blue]text")).id();
+
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let world = app.world();
+        let span = world.get::<Children>(ent).unwrap()[0];
+        *world.get::<TextColor>(span).unwrap()
+    }
+
+    assert_eq!(span_color(RichTextConfig::default()).0, BLUE.into());
+    assert_eq!(
+        span_color(RichTextConfig {
+            tag_order: TagApplicationOrder::LeftToRightEarliestWins,
+            ..Default::default()
+        })
+        .0,
+        RED.into()
+    );
+}
+
+#[test]
+fn test_local_default_style() {
+    use bevy::{
+        app::App,
+        color::palettes::css::{GREEN, RED},
+        prelude::{Children, TextColor},
+    };
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    app.world_mut()
+        .spawn((StyleTag::new("red"), TextColor(RED.into())));
+    let local = app.world_mut().spawn(TextColor(GREEN.into())).id();
+    let ent = app
+        .world_mut()
+        .spawn((RichText::new("a[red]b"), LocalDefaultStyle(local)))
+        .id();
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let world = app.world();
+    let children = world.get::<Children>(ent).unwrap();
+    assert_eq!(world.get::<TextColor>(children[0]).unwrap().0, GREEN.into());
+    assert_eq!(world.get::<TextColor>(children[1]).unwrap().0, RED.into());
+}
+
+#[test]
+fn test_spawn_richtext_batch() {
+    use bevy::{
+        app::App,
+        ecs::system::RunSystemOnce,
+        prelude::{Children, Commands},
+    };
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ents = app
+        .world_mut()
+        .run_system_once(|mut commands: Commands| {
+            spawn_richtext_batch(
+                &mut commands,
+                [RichText::new("a"), RichText::new("b[red]c")],
+            )
+        })
+        .unwrap();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(ents.len(), 2);
+    assert_eq!(world.get::<Children>(ents[0]).unwrap().len(), 1);
+    assert_eq!(world.get::<Children>(ents[1]).unwrap().len(), 2);
+    assert!(!world.entity(ents[1]).contains::<RebuildRichText>());
+}
+
+#[test]
+fn test_no_default_style() {
+    use bevy::{
+        app::App,
+        color::palettes::css::{BLUE, RED},
+        prelude::Children,
+    };
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>()
+        .register_type::<TextFont>();
+
+    let default = app
+        .world()
+        .resource::<StyleTags>()
+        .try_get_default()
+        .unwrap();
+    app.world_mut()
+        .entity_mut(default)
+        .insert((TextColor(RED.into()), TextFont::from_font_size(40.)));
+    app.world_mut()
+        .spawn((StyleTag::new("blue"), TextColor(BLUE.into())));
+    let ent = app
+        .world_mut()
+        .spawn((RichText::new("a[blue]b"), NoDefaultStyle))
+        .id();
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let world = app.world();
+    let children = world.get::<Children>(ent).unwrap();
+    assert_ne!(world.get::<TextColor>(children[0]).unwrap().0, RED.into());
+    assert_eq!(world.get::<TextColor>(children[1]).unwrap().0, BLUE.into());
+    for child in children {
+        assert_ne!(world.get::<TextFont>(*child).unwrap().font_size, 40.);
+    }
+}
+
+#[test]
+fn test_with_local_tag() {
+    use bevy::{
+        app::App,
+        color::palettes::css::{BLUE, GREEN, RED},
+        prelude::{Children, TextColor},
+    };
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    app.world_mut()
+        .spawn((StyleTag::new("a"), TextColor(RED.into())));
+    app.world_mut()
+        .spawn((StyleTag::new("b"), TextColor(RED.into())));
+    let green = app.world_mut().spawn(TextColor(GREEN.into())).id();
+    let blue = app.world_mut().spawn(TextColor(BLUE.into())).id();
+    let ent = app
+        .world_mut()
+        .spawn(
+            RichText::new("[a]a[b]b[c]c")
+                .with_local_tag("a", green)
+                .with_local_tag("c", blue),
+        )
+        .id();
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let world = app.world();
+    let children = world.get::<Children>(ent).unwrap();
+    assert_eq!(world.get::<TextColor>(children[0]).unwrap().0, GREEN.into());
+    assert_eq!(world.get::<TextColor>(children[1]).unwrap().0, RED.into());
+    assert_eq!(world.get::<TextColor>(children[2]).unwrap().0, BLUE.into());
+}
+
+#[test]
+fn test_inline_tags() {
+    use bevy::{
+        app::App,
+        color::palettes::css::{BLUE, RED},
+        prelude::Children,
+    };
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>()
+        .register_type::<TextFont>();
+
+    app.world_mut()
+        .spawn((StyleTag::new("blue"), TextColor(BLUE.into())));
+    app.world_mut()
+        .spawn((StyleTag::new("lg"), TextFont::from_font_size(40.)));
+    let ent = app
+        .world_mut()
+        .spawn(RichText::new(
+            "[#ff0000]a[#ff0000,blue]b[size=24]c[size=24,lg]d[alpha=0.5,blue]e",
+        ))
+        .id();
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let world = app.world();
+    let children = world.get::<Children>(ent).unwrap();
+    assert_eq!(world.get::<TextColor>(children[0]).unwrap().0, RED.into());
+    assert_eq!(world.get::<TextColor>(children[1]).unwrap().0, BLUE.into());
+    assert_eq!(world.get::<TextFont>(children[2]).unwrap().font_size, 24.);
+    assert_eq!(world.get::<TextFont>(children[3]).unwrap().font_size, 40.);
+    assert_eq!(
+        world.get::<TextColor>(children[4]).unwrap().0,
+        Color::from(BLUE).with_alpha(0.5)
+    );
+}
+
+#[test]
+fn test_scene_round_trip() {
+    use bevy::{
+        ecs::{entity::EntityHashMap, reflect::AppTypeRegistry},
+        scene::{ron, serde::SceneDeserializer, DynamicSceneBuilder},
+    };
+    use serde::de::DeserializeSeed;
+
+    let mut world = World::new();
+    let registry = AppTypeRegistry::default();
+    {
+        let mut registry = registry.write();
+        registry.register::<RichText>();
+        registry.register::<RichText2d>();
+        registry.register::<StyleTag>();
+    }
+    world.insert_resource(registry.clone());
+
+    let ent = world
+        .spawn((
+            RichText::new("[red]a"),
+            RichText2d::new("b"),
+            StyleTag::new("c"),
+        ))
+        .id();
+
+    let scene = DynamicSceneBuilder::from_world(&world)
+        .deny_all()
+        .allow_component::<RichText>()
+        .allow_component::<RichText2d>()
+        .allow_component::<StyleTag>()
+        .extract_entity(ent)
+        .build();
+    let serialized = scene.serialize(&registry.read()).unwrap();
+
+    let scene = SceneDeserializer {
+        type_registry: &registry.read(),
+    }
+    .deserialize(&mut ron::Deserializer::from_str(&serialized).unwrap())
+    .unwrap();
+
+    let mut world = World::new();
+    world.insert_resource(registry);
+    let mut entity_map = EntityHashMap::default();
+    scene.write_to_world(&mut world, &mut entity_map).unwrap();
+
+    let ent = entity_map[&ent];
+    assert_eq!(world.get::<RichText>(ent).unwrap().0, "[red]a");
+    assert_eq!(world.get::<RichText2d>(ent).unwrap().0, "b");
+    assert_eq!(world.get::<StyleTag>(ent).unwrap().0, "c");
+}
+
+#[test]
+fn test_set_order() {
+    use bevy::{app::App, color::Color, prelude::Children};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    app.world_mut()
+        .spawn((StyleTag::new("red"), TextColor(Color::srgb(1., 0., 0.))));
+    let ent = app.world_mut().spawn(RichText::new("[red]a")).id();
+
+    // The registry is synced before rebuilding, so one frame is enough.
+    app.update();
+
+    let world = app.world();
+    let span = world.get::<Children>(ent).unwrap()[0];
+    assert_eq!(
+        world.get::<TextColor>(span).unwrap().0,
+        Color::srgb(1., 0., 0.)
+    );
+}
+
+#[test]
+fn test_aliases() {
+    use bevy::{app::App, color::Color, prelude::Children};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    let bold = app
+        .world_mut()
+        .spawn((
+            StyleTag::new("bold"),
+            Aliases(vec!["b".to_string()]),
+            TextColor(Color::srgb(1., 0., 0.)),
+        ))
+        .id();
+    let long = app.world_mut().spawn(RichText::new("[bold]text")).id();
+    let short = app.world_mut().spawn(RichText::new("[b]text")).id();
+    app.update();
+
+    let color = |app: &App, ent: Entity| {
+        let world = app.world();
+        let span = world.get::<Children>(ent).unwrap()[0];
+        world.get::<TextColor>(span).unwrap().0
+    };
+    assert_eq!(color(&app, long), Color::srgb(1., 0., 0.));
+    assert_eq!(color(&app, short), color(&app, long));
+
+    app.world_mut().despawn(bold);
+    app.update();
+
+    let registry = app.world().resource::<StyleTags>();
+    assert!(!registry.contains("bold"));
+    assert!(!registry.contains("b"));
+}
+
+#[test]
+fn test_tag_groups() {
+    use bevy::{app::App, color::Color, prelude::Children};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>()
+        .register_type::<TextFont>();
+
+    app.world_mut()
+        .spawn((StyleTag::new("large"), TextFont::from_font_size(40.)));
+    app.world_mut()
+        .spawn((StyleTag::new("accent"), TextColor(Color::srgb(1., 0., 0.))));
+    app.world_mut()
+        .spawn(TagGroup::new("header", ["large", "accent"]));
+    app.world_mut().spawn(TagGroup::new("title", ["header"]));
+    let header = app.world_mut().spawn(RichText::new("[header]Hello")).id();
+    let title = app.world_mut().spawn(RichText::new("[title]Hello")).id();
+    app.update();
+
+    for ent in [header, title] {
+        let world = app.world();
+        let span = world.get::<Children>(ent).unwrap()[0];
+        assert_eq!(world.get::<TextFont>(span).unwrap().font_size, 40.);
+        assert_eq!(
+            world.get::<TextColor>(span).unwrap().0,
+            Color::srgb(1., 0., 0.)
+        );
+    }
+
+    let groups = HashMap::from([
+        ("a".to_string(), vec!["b".to_string()]),
+        ("b".to_string(), vec!["c".to_string()]),
+        ("c".to_string(), vec!["red".to_string()]),
+    ]);
+    let mut expanded = vec![];
+    expand_tag_groups(["a".to_string()], &groups, 0, &mut expanded);
+    assert!(expanded.is_empty());
+}
+
+#[test]
+fn test_remove_and_swap() {
+    use bevy::{app::App, color::Color, prelude::Children};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    let red = Color::srgb(1., 0., 0.);
+    let blue = Color::srgb(0., 0., 1.);
+
+    let red_ent = app
+        .world_mut()
+        .spawn((StyleTag::new("accent"), TextColor(red)))
+        .id();
+    let blue_ent = app
+        .world_mut()
+        .spawn((StyleTag::new("blue"), TextColor(blue)))
+        .id();
+    let ent = app.world_mut().spawn(RichText::new("[accent]a")).id();
+    app.update();
+
+    let color = |app: &App| {
+        let world = app.world();
+        let span = world.get::<Children>(ent).unwrap()[0];
+        world.get::<TextColor>(span).map(|color| color.0)
+    };
+    assert_eq!(color(&app), Some(red));
+
+    let previous = app
+        .world_mut()
+        .resource_mut::<StyleTags>()
+        .swap("accent", blue_ent);
+    assert_eq!(previous, Some(red_ent));
+    app.update();
+    assert_eq!(color(&app), Some(blue));
+
+    let removed = app.world_mut().resource_mut::<StyleTags>().remove("accent");
+    assert_eq!(removed, Some(blue_ent));
+    app.update();
+    assert_eq!(color(&app), Some(TextColor::default().0));
+    assert!(app.world().get_entity(blue_ent).is_ok());
+}
+
+#[test]
+fn test_registry_changed_2d() {
+    use bevy::{app::App, color::Color, prelude::Children};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    let red = Color::srgb(1., 0., 0.);
+    let blue = Color::srgb(0., 0., 1.);
+
+    let style = app
+        .world_mut()
+        .spawn((StyleTag::new("accent"), TextColor(red)))
+        .id();
+    let ui = app.world_mut().spawn(RichText::new("[accent]a")).id();
+    let world_2d = app.world_mut().spawn(RichText2d::new("[accent]a")).id();
+    app.update();
+
+    app.world_mut().get_mut::<TextColor>(style).unwrap().0 = blue;
+    app.world_mut().resource_mut::<StyleTags>().set_changed();
+    app.update();
+
+    for ent in [ui, world_2d] {
+        let world = app.world();
+        let span = world.get::<Children>(ent).unwrap()[0];
+        assert_eq!(world.get::<TextColor>(span).unwrap().0, blue);
+    }
+}
+
+#[test]
+fn test_style_refresh() {
+    use bevy::{app::App, color::Color, ecs::event::Events};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    let red = Color::srgb(1., 0., 0.);
+    let blue = Color::srgb(0., 0., 1.);
+
+    let style = app
+        .world_mut()
+        .spawn((StyleTag::new("accent"), TextColor(red)))
+        .id();
+    let ent = app.world_mut().spawn(RichText::new("a[accent]b")).id();
+    app.update();
+
+    let spans = app.world().get::<Children>(ent).unwrap().to_vec();
+
+    app.world_mut().get_mut::<TextColor>(style).unwrap().0 = blue;
+    app.world_mut().resource_mut::<StyleTags>().set_changed();
+    app.update();
+
+    // The same spans are restyled rather than respawned.
+    let world = app.world();
+    assert_eq!(world.get::<Children>(ent).unwrap().to_vec(), spans);
+    assert_eq!(
+        world.get::<TextColor>(spans[0]).unwrap().0,
+        TextColor::default().0
+    );
+    assert_eq!(world.get::<TextColor>(spans[1]).unwrap().0, blue);
+    let events = world.resource::<Events<RichTextBuilt>>();
+    assert_eq!(events.iter_current_update_events().count(), 0);
+}
+
+#[test]
+fn test_span_char_range() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ent = app
+        .world_mut()
+        .spawn(RichText::new("héllo[red] wörld[]!"))
+        .id();
+    app.update();
+
+    let world = app.world();
+    let ranges = world
+        .get::<Children>(ent)
+        .unwrap()
+        .iter()
+        .map(|child| *world.get::<SpanCharRange>(*child).unwrap())
+        .map(|range| (range.start, range.end))
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, vec![(0, 5), (5, 11), (11, 12)]);
+}
+
+#[test]
+fn test_tag_users() {
+    use bevy::{app::App, color::Color};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    let red = Color::srgb(1., 0., 0.);
+    let blue = Color::srgb(0., 0., 1.);
+
+    let style = app
+        .world_mut()
+        .spawn((StyleTag::new("accent"), TextColor(red)))
+        .id();
+    let uses = app.world_mut().spawn(RichText::new("[accent]a")).id();
+    let other = app.world_mut().spawn(RichText::new("b")).id();
+    app.update();
+
+    let users = app.world().resource::<StyleTagUsers>();
+    assert_eq!(users.tag_users("accent").collect::<Vec<_>>(), vec![uses]);
+
+    // Only the text using the changed tag is restyled.
+    let other_span = app.world().get::<Children>(other).unwrap()[0];
+    app.world_mut().get_mut::<TextColor>(other_span).unwrap().0 = blue;
+    app.world_mut().get_mut::<TextColor>(style).unwrap().0 = blue;
+    app.world_mut()
+        .resource_mut::<StyleTagUsers>()
+        .mark_changed("accent");
+    app.update();
+
+    let world = app.world();
+    let span = world.get::<Children>(uses).unwrap()[0];
+    assert_eq!(world.get::<TextColor>(span).unwrap().0, blue);
+    assert_eq!(world.get::<TextColor>(other_span).unwrap().0, blue);
+
+    app.world_mut().entity_mut(uses).insert(RichText::new("c"));
+    app.update();
+    let users = app.world().resource::<StyleTagUsers>();
+    assert_eq!(users.tag_users("accent").count(), 0);
+
+    // Despawned text is forgotten.
+    let despawned = app.world_mut().spawn(RichText::new("[accent]d")).id();
+    app.update();
+    app.world_mut().entity_mut(despawned).despawn_recursive();
+    app.update();
+    let users = app.world().resource::<StyleTagUsers>();
+    assert_eq!(users.tag_users("accent").count(), 0);
+}
+
+#[test]
+fn test_tag_priority() {
+    use bevy::{app::App, color::Color};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    let red = Color::srgb(1., 0., 0.);
+    let blue = Color::srgb(0., 0., 1.);
+
+    app.world_mut()
+        .spawn((StyleTag::new("low"), TagPriority(-1), TextColor(red)));
+    app.world_mut()
+        .spawn((StyleTag::new("high"), TagPriority(1), TextColor(blue)));
+    let ents = [
+        app.world_mut().spawn(RichText::new("[low,high]a")).id(),
+        app.world_mut().spawn(RichText::new("[high,low]a")).id(),
+    ];
+    app.update();
+
+    for ent in ents {
+        let world = app.world();
+        let span = world.get::<Children>(ent).unwrap()[0];
+        assert_eq!(world.get::<TextColor>(span).unwrap().0, blue);
+    }
+}
+
+#[test]
+fn test_inline_style() {
+    use bevy::{app::App, color::Color};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>()
+        .register_type::<TextFont>();
+
+    let red = Color::srgb(1., 0., 0.);
+
+    app.world_mut()
+        .spawn((StyleTag::new("unique"), TextFont::from_font_size(10.)));
+    let ent = app
+        .world_mut()
+        .spawn((
+            RichText::new("a[unique]b"),
+            InlineStyle::new("unique")
+                .with(TextColor(red))
+                .with(TextFont::from_font_size(40.)),
+        ))
+        .id();
+    let other = app.world_mut().spawn(RichText::new("[unique]c")).id();
+    app.update();
+
+    let world = app.world();
+    let spans = world.get::<Children>(ent).unwrap();
+    assert_eq!(
+        world.get::<TextColor>(spans[0]).unwrap().0,
+        TextColor::default().0
+    );
+    assert_eq!(world.get::<TextColor>(spans[1]).unwrap().0, red);
+    assert_eq!(world.get::<TextFont>(spans[1]).unwrap().font_size, 40.);
+
+    let span = world.get::<Children>(other).unwrap()[0];
+    assert_eq!(world.get::<TextFont>(span).unwrap().font_size, 10.);
+}
+
+#[test]
+fn test_style_tag_bundle() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>()
+        .register_type::<TextFont>();
+
+    let red = Color::srgb(1., 0., 0.);
+
+    let default = *app.world().resource::<StyleTags>().get_default();
+    app.world_mut()
+        .entity_mut(default)
+        .insert(TextFont::from_font_size(30.));
+    app.world_mut().spawn(StyleTagBundle::new(
+        "title",
+        red,
+        TextFont::from_font_size(40.),
+    ));
+    app.world_mut().spawn(StyleTagBundle::color("red", red));
+    let ent = app.world_mut().spawn(RichText::new("[title]a[red]b")).id();
+    app.update();
+
+    let world = app.world();
+    let spans = world.get::<Children>(ent).unwrap();
+    assert_eq!(world.get::<TextColor>(spans[0]).unwrap().0, red);
+    assert_eq!(world.get::<TextFont>(spans[0]).unwrap().font_size, 40.);
+    assert_eq!(world.get::<TextColor>(spans[1]).unwrap().0, red);
+    assert_eq!(world.get::<TextFont>(spans[1]).unwrap().font_size, 30.);
+}
+
+#[test]
+fn test_style_tag_hierarchy() {
+    use bevy::{
+        app::App,
+        hierarchy::{ChildBuild, HierarchyPlugin},
+    };
+
+    let mut app = App::new();
+    app.add_plugins((HierarchyPlugin, RichTextPlugin::default()))
+        .register_type::<TextColor>();
+
+    let red = Color::srgb(1., 0., 0.);
+
+    let root = app
+        .world_mut()
+        .spawn_empty()
+        .with_children(|parent| {
+            parent.spawn((StyleTag::new("red"), TextColor(red)));
+        })
+        .id();
+    let ent = app.world_mut().spawn(RichText::new("a[red]b")).id();
+    app.update();
+
+    let world = app.world();
+    assert!(world.resource::<StyleTags>().contains("red"));
+
+    let spans = world.get::<Children>(ent).unwrap();
+    assert_eq!(spans.len(), 2);
+    for span in spans {
+        assert_eq!(world.get::<Parent>(*span).unwrap().get(), ent);
+    }
+    assert_eq!(world.get::<TextColor>(spans[1]).unwrap().0, red);
+    assert_eq!(world.get::<Children>(root).unwrap().len(), 1);
+}
+
+#[test]
+fn test_richtext_disabled() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ent = app
+        .world_mut()
+        .spawn((RichText::new("a[red]b"), RichTextDisabled))
+        .id();
+    app.update();
+    assert!(app.world().get::<Children>(ent).is_none());
+
+    app.world_mut().get_mut::<RichText>(ent).unwrap().0 = "a".to_string();
+    app.update();
+    assert!(app.world().get::<Children>(ent).is_none());
+
+    app.world_mut().entity_mut(ent).remove::<RichTextDisabled>();
+    app.update();
+    let world = app.world();
+    let spans = world.get::<Children>(ent).unwrap();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(world.get::<TextSpan>(spans[0]).unwrap().0, "a");
+}
+
+#[test]
+fn test_mutation_helpers() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ent = app.world_mut().spawn(RichText::new("b")).id();
+    app.update();
+
+    let mut rich_text = app.world_mut().get_mut::<RichText>(ent).unwrap();
+    rich_text.append("[red]c");
+    rich_text.prepend("a ");
+    assert_eq!(rich_text.0, "a b[red]c");
+    app.update();
+    assert_eq!(app.world().get::<Children>(ent).unwrap().len(), 2);
+
+    let mut rich_text2d = RichText2d::new("x");
+    rich_text2d.set("y");
+    assert_eq!(rich_text2d.0, "y");
+    rich_text2d.clear();
+    assert!(rich_text2d.0.is_empty());
+}
+
+#[test]
+fn test_span_reuse() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+    app.world_mut().spawn(StyleTag::new("red"));
+
+    let ent = app.world_mut().spawn(RichText::new("a[red]Hello")).id();
+    app.update();
+
+    let children = |app: &App| app.world().get::<Children>(ent).unwrap().to_vec();
+    let before = children(&app);
+
+    app.world_mut()
+        .get_mut::<RichText>(ent)
+        .unwrap()
+        .set("b[red]World");
+    app.update();
+    let after = children(&app);
+    assert_eq!(before, after);
+    assert_eq!(app.world().get::<TextSpan>(after[1]).unwrap().0, "World");
+    assert_eq!(
+        *app.world().get::<SpanCharRange>(after[1]).unwrap(),
+        SpanCharRange { start: 1, end: 6 }
+    );
+
+    app.world_mut()
+        .get_mut::<RichText>(ent)
+        .unwrap()
+        .set("[red]b[]World");
+    app.update();
+    let rebuilt = children(&app);
+    assert_eq!(rebuilt.len(), 2);
+    assert!(rebuilt.iter().all(|span| !before.contains(span)));
+    assert!(before
+        .iter()
+        .all(|span| app.world().get_entity(*span).is_err()));
+}
+
+#[test]
+fn test_despawned_style_tag() {
+    use bevy::{
+        app::App,
+        prelude::{Commands, IntoSystemConfigs, Query, Update},
+    };
+
+    fn despawn_red(
+        mut commands: Commands,
+        styles: Query<(Entity, &StyleTag)>,
+        mut rich_text: Query<&mut RichText>,
+    ) {
+        for (ent, style) in &styles {
+            if style.0 == "red" {
+                commands.entity(ent).despawn();
+                for mut rich_text in &mut rich_text {
+                    rich_text.set("[red]b");
+                }
+            }
+        }
+    }
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+    app.world_mut()
+        .spawn((StyleTag::new("red"), TextColor(Color::WHITE)));
+    let local = app.world_mut().spawn(TextColor(Color::BLACK)).id();
+    let ent = app
+        .world_mut()
+        .spawn((
+            RichText::new("[red]a[local]c"),
+            LocalStyleTags([("local".to_string(), local)].into()),
+        ))
+        .id();
+    app.update();
+
+    // Despawn the style after the registry is synced, but before the rich text
+    // is rebuilt, along with a local style.
+    app.add_systems(
+        Update,
+        despawn_red
+            .after(RichTextSet::Sync)
+            .before(RichTextSet::Rebuild),
+    );
+    app.world_mut().despawn(local);
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<StyleTags>().get("red"), None);
+    let world = app.world();
+    let span = world.get::<Children>(ent).unwrap()[0];
+    assert_eq!(world.get::<TextSpan>(span).unwrap().0, "b");
+}
+
+#[test]
+fn test_previous_markup() {
+    use bevy::{app::App, ecs::event::Events};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ent = app.world_mut().spawn(RichText::new("a[red]b")).id();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<PreviousMarkup>(ent).unwrap().0, "a[red]b");
+    assert_eq!(
+        world.get::<TextSectionCount>(ent),
+        Some(&TextSectionCount(2))
+    );
+    let spans = world.get::<Children>(ent).unwrap().to_vec();
+    app.world_mut()
+        .resource_mut::<Events<RichTextBuilt>>()
+        .clear();
+
+    // Changing the component without changing the markup doesn't rebuild it.
+    app.world_mut()
+        .get_mut::<RichText>(ent)
+        .unwrap()
+        .set("a[red]b");
+    app.update();
+    assert!(app.world().resource::<Events<RichTextBuilt>>().is_empty());
+
+    // Rebuilds can still be forced.
+    app.world_mut().entity_mut(ent).insert(RebuildRichText);
+    app.update();
+    assert_eq!(app.world().resource::<Events<RichTextBuilt>>().len(), 1);
+    assert_eq!(app.world().get::<Children>(ent).unwrap().to_vec(), spans);
+    assert!(!app.world().entity(ent).contains::<RebuildRichText>());
+
+    // Config changes apply to existing text.
+    app.world_mut()
+        .resource_mut::<RichTextConfig>()
+        .collapse_whitespace = true;
+    app.update();
+    assert_eq!(app.world().resource::<Events<RichTextBuilt>>().len(), 2);
+}
+
+#[test]
+fn test_process_escape_sequences() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::with_config(RichTextConfig {
+        process_escape_sequences: true,
+        ..Default::default()
+    }));
+
+    let ent = app.world_mut().spawn(RichText::new(r"Hello\nWorld")).id();
+    app.update();
+
+    let world = app.world();
+    let span = world.get::<Children>(ent).unwrap()[0];
+    assert_eq!(world.get::<TextSpan>(span).unwrap().0, "Hello\nWorld");
+}
+
+#[test]
+fn test_strict_failure() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::with_config(RichTextConfig {
+        strict: true,
+        ..Default::default()
+    }));
+
+    let ent = app
+        .world_mut()
+        .spawn((RichText::new("[red"), RebuildRichText))
+        .id();
+    app.update();
+
+    // The failed markup isn't parsed again until it changes.
+    assert!(!app.world().entity(ent).contains::<RebuildRichText>());
+    assert!(markup_unchanged(app.world_mut(), ent));
+    assert!(app.world().get::<Children>(ent).is_none());
+
+    app.world_mut()
+        .entity_mut(ent)
+        .insert(RichText::new("[red]a"));
+    app.update();
+    assert_eq!(app.world().get::<Children>(ent).unwrap().len(), 1);
+}
+
+#[test]
+fn test_case_insensitive_tags() {
+    use bevy::{
+        app::App,
+        color::palettes::css::{BLUE, RED},
+    };
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::with_config(RichTextConfig {
+        case_insensitive_tags: true,
+        ..Default::default()
+    }))
+    .register_type::<TextColor>();
+
+    app.world_mut()
+        .spawn((StyleTag::new("red"), TextColor(RED.into())));
+    app.world_mut()
+        .spawn((StyleTag::new("Blue"), TextColor(BLUE.into())));
+    let ent = app
+        .world_mut()
+        .spawn(RichText::new("[RED]text[blue]text"))
+        .id();
+    app.update();
+
+    let world = app.world();
+    assert!(world.resource::<StyleTags>().contains_key("blue"));
+    let children = world.get::<Children>(ent).unwrap();
+    assert_eq!(world.get::<TextColor>(children[0]).unwrap().0, RED.into());
+    assert_eq!(world.get::<TextColor>(children[1]).unwrap().0, BLUE.into());
+}
+
+#[test]
+fn test_build_on_insert() {
+    use bevy::app::App;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::with_config(RichTextConfig {
+        build_on_insert: true,
+        ..Default::default()
+    }));
+    app.update();
+
+    let ent = app.world_mut().spawn(RichText::new("a[red]b")).id();
+    app.world_mut().flush();
+    assert_eq!(app.world().get::<Children>(ent).unwrap().len(), 2);
+
+    let ent_2d = app.world_mut().spawn(RichText2d::new("c")).id();
+    app.world_mut().flush();
+    assert_eq!(app.world().get::<Children>(ent_2d).unwrap().len(), 1);
+
+    // The spans aren't rebuilt again later.
+    let spans = app.world().get::<Children>(ent).unwrap().to_vec();
+    app.update();
+    assert_eq!(app.world().get::<Children>(ent).unwrap().to_vec(), spans);
+}
+
+#[test]
+fn test_spawn_or_update() {
+    use bevy::{app::App, color::Color, ecs::world::CommandQueue};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>()
+        .register_type::<TextFont>();
+
+    let style = app
+        .world_mut()
+        .spawn((
+            StyleTag::new("text"),
+            Aliases(vec!["body".to_string()]),
+            TagPriority(5),
+            TextColor(Color::WHITE),
+            TextFont::from_font_size(30.),
+        ))
+        .id();
+    app.update();
+
+    app.world_mut()
+        .resource_scope(|world, mut registry: Mut<StyleTags>| {
+            let mut queue = CommandQueue::default();
+            let mut commands = Commands::new(&mut queue, world);
+            let updated = registry.spawn_or_update(&mut commands, "body", TextColor(Color::BLACK));
+            assert_eq!(updated, style);
+            queue.apply(world);
+        });
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<TextColor>(style).unwrap().0, Color::BLACK);
+    assert!(world.get::<TextFont>(style).is_none());
+    assert_eq!(world.get::<StyleTag>(style).unwrap().0, "text");
+    assert_eq!(
+        world.get::<Aliases>(style).unwrap().0,
+        vec!["body".to_string()]
+    );
+    assert_eq!(world.get::<TagPriority>(style), Some(&TagPriority(5)));
+    assert_eq!(
+        world.resource::<StyleTags>().get_or_none("text"),
+        Some(style)
+    );
+}
+
+#[test]
+fn test_swap_components() {
+    use bevy::{
+        app::App,
+        ecs::world::CommandQueue,
+        prelude::{Commands, Mut},
+    };
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    let light = app
+        .world_mut()
+        .spawn((StyleTag::new("text"), TextColor(Color::WHITE)))
+        .id();
+    let dark = app.world_mut().spawn(TextColor(Color::BLACK)).id();
+    let ent = app.world_mut().spawn(RichText::new("[text]a")).id();
+    app.update();
+
+    app.world_mut()
+        .resource_scope(|world, registry: Mut<StyleTags>| {
+            let mut queue = CommandQueue::default();
+            let mut commands = Commands::new(&mut queue, world);
+            registry.swap_components(&mut commands, dark, light);
+            queue.apply(world);
+        });
+    app.update();
+
+    let world = app.world();
+    let span = world.get::<Children>(ent).unwrap()[0];
+    assert_eq!(world.get::<TextColor>(span).unwrap().0, Color::BLACK);
+    assert_eq!(world.get::<TextColor>(light).unwrap().0, Color::BLACK);
+    assert!(world.get::<TextColor>(dark).is_none());
+    assert_eq!(world.resource::<StyleTags>().get("text"), Some(&light));
+
+    // Swapping a style with itself keeps its components.
+    app.world_mut()
+        .resource_scope(|world, registry: Mut<StyleTags>| {
+            let mut queue = CommandQueue::default();
+            let mut commands = Commands::new(&mut queue, world);
+            registry.swap_components(&mut commands, light, light);
+            queue.apply(world);
+        });
+    app.update();
+
+    let world = app.world();
+    let span = world.get::<Children>(ent).unwrap()[0];
+    assert_eq!(world.get::<TextColor>(span).unwrap().0, Color::BLACK);
+    assert_eq!(world.get::<TextColor>(light).unwrap().0, Color::BLACK);
+}
+
+#[test]
+fn test_inline_font() {
+    use bevy::{
+        app::App,
+        asset::{AssetApp, AssetPlugin},
+        core::TaskPoolPlugin,
+    };
+
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        AssetPlugin::default(),
+        RichTextPlugin::default(),
+    ))
+    .init_asset::<Font>();
+
+    let ent = app
+        .world_mut()
+        .spawn(RichText::new("[font=fonts/bold.ttf,size=30]a"))
+        .id();
+    app.update();
+
+    let world = app.world();
+    let span = world.get::<Children>(ent).unwrap()[0];
+    let font = world.get::<TextFont>(span).unwrap();
+    assert_eq!(font.font_size, 30.);
+    assert_eq!(
+        font.font.path(),
+        Some(&bevy::asset::AssetPath::from("fonts/bold.ttf"))
+    );
+}
+
+#[test]
+fn test_style_tag_label() {
+    use bevy::{app::App, color::palettes::css::RED};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default())
+        .register_type::<TextColor>();
+
+    app.world_mut().spawn((
+        StyleTag::new("red"),
+        StyleTagLabel("menu".to_string()),
+        TextColor(RED.into()),
+    ));
+    let menu = app
+        .world_mut()
+        .spawn((
+            RichText::new("[red]a"),
+            RichTextAcceptsLabels(vec!["menu".to_string()]),
+        ))
+        .id();
+    let other = app.world_mut().spawn(RichText::new("[red]a")).id();
+    app.update();
+
+    let span_color = |app: &App, ent: Entity| {
+        let world = app.world();
+        let span = world.get::<Children>(ent).unwrap()[0];
+        world.get::<TextColor>(span).unwrap().0
+    };
+    assert_eq!(span_color(&app, menu), RED.into());
+    assert_eq!(span_color(&app, other), TextColor::default().0);
+
+    app.world_mut()
+        .entity_mut(other)
+        .insert(RichTextAcceptsLabels(vec!["menu".to_string()]));
+    app.update();
+    assert_eq!(span_color(&app, other), RED.into());
+}
+
+#[test]
+fn test_custom_schedule() {
+    use bevy::{app::App, prelude::Children};
+
+    #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+    struct Custom;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::new(Custom));
+
+    let ent = app.world_mut().spawn(RichText::new("a[red]b")).id();
+
+    app.update();
+    assert!(app.world().get::<Children>(ent).is_none());
+
+    app.world_mut().run_schedule(Custom);
+    assert_eq!(app.world().get::<Children>(ent).unwrap().len(), 2);
+}
+
+#[test]
+fn test_custom_schedule_companion_plugins() {
+    use bevy::{app::App, ecs::system::Resource};
+
+    #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+    struct Custom;
+
+    #[derive(Resource)]
+    struct Score(u32);
+
+    let mut app = App::new();
+    app.add_plugins((RichTextPlugin::new(Custom), RichTextPlugin::bind::<Score>()))
+        .insert_resource(Score(1));
+    assert_eq!(
+        **app.world().resource::<RichTextSchedule>(),
+        Custom.intern()
+    );
+
+    let ent = app
+        .world_mut()
+        .spawn((
+            RichText::new(""),
+            RichTextBinding::new(|score: &Score| format!("{}", score.0)),
+        ))
+        .id();
+
+    // The binding is updated in the same schedule as the rich text.
+    app.update();
+    assert_eq!(app.world().get::<RichText>(ent).unwrap().0, "");
+
+    app.world_mut().run_schedule(Custom);
+    assert_eq!(app.world().get::<RichText>(ent).unwrap().0, "1");
+}
+
+#[test]
+fn test_post_update_ordering() {
+    use bevy::{app::App, ecs::system::Resource};
+
+    #[derive(Resource, Default)]
+    struct SpanCounts(Vec<usize>);
+
+    fn count_spans(query: Query<&Children, With<RichText>>, mut counts: ResMut<SpanCounts>) {
+        counts
+            .0
+            .push(query.iter().map(|children| children.len()).sum());
+    }
+
+    // Added before the plugin, so that without explicit ordering these would
+    // likely run before the spans are spawned, delaying them by a frame.
+    let mut app = App::new();
+    app.init_resource::<SpanCounts>().add_systems(
+        PostUpdate,
+        (
+            count_spans.in_set(UiSystem::Prepare),
+            count_spans.in_set(UiSystem::Layout),
+            count_spans.in_set(Update2dText),
+            count_spans.in_set(TransformSystem::TransformPropagate),
+        ),
+    );
+    app.add_plugins(RichTextPlugin::new(PostUpdate));
+
+    app.world_mut().spawn(RichText::new("a[red]b"));
+    app.update();
+
+    assert_eq!(app.world().resource::<SpanCounts>().0, vec![2; 4]);
+}
+
+#[test]
+fn test_richtext_built() {
+    use bevy::{app::App, ecs::event::Events, prelude::Children};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ent = app.world_mut().spawn(RichText::new("a[red]b")).id();
+    app.update();
+
+    let events = app.world().resource::<Events<RichTextBuilt>>();
+    let built = events
+        .iter_current_update_events()
+        .map(|event| event.entity)
+        .collect::<Vec<_>>();
+    assert_eq!(built, vec![ent]);
+    assert_eq!(app.world().get::<Children>(ent).unwrap().len(), 2);
+}
+
+#[test]
+fn test_source_tags() {
+    use bevy::{app::App, prelude::Children};
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ent = app.world_mut().spawn(RichText::new("a[red,lg]b")).id();
+    app.update();
+
+    let world = app.world();
+    let children = world.get::<Children>(ent).unwrap();
+    assert_eq!(world.get::<SourceTags>(children[0]).unwrap().0, vec![""]);
+    assert_eq!(
+        world.get::<SourceTags>(children[1]).unwrap().0,
+        vec!["", "red", "lg"]
+    );
+}
+
+#[test]
+fn test_unknown_tags() {
+    use bevy::{app::App, prelude::Children};
+
+    fn span_count(unknown_tags: UnknownTagBehavior) -> usize {
+        let mut app = App::new();
+        app.add_plugins(RichTextPlugin::with_config(RichTextConfig {
+            unknown_tags,
+            ..Default::default()
+        }));
+
+        let ent = app.world_mut().spawn(RichText::new("a[typo]b[]c")).id();
+        app.update();
+
+        app.world().get::<Children>(ent).unwrap().len()
+    }
+
+    assert_eq!(span_count(UnknownTagBehavior::Warn), 3);
+    assert_eq!(span_count(UnknownTagBehavior::Ignore), 3);
+    assert_eq!(span_count(UnknownTagBehavior::UseDefault), 3);
+    assert_eq!(span_count(UnknownTagBehavior::Error), 2);
+}
+
+#[test]
+fn test_unknown_tag_warned_once() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use bevy::{
+        app::App,
+        log::{
+            tracing_subscriber::{
+                layer::{Context, SubscriberExt},
+                registry::Registry,
+                Layer,
+            },
+            Level,
+        },
+        utils::tracing::{self, Subscriber},
+    };
+
+    struct CountWarnings(Arc<AtomicUsize>);
+    impl<S: Subscriber> Layer<S> for CountWarnings {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            if *event.metadata().level() == Level::WARN {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    let warnings = Arc::new(AtomicUsize::new(0));
+    let subscriber = Registry::default().with(CountWarnings(warnings.clone()));
+
+    tracing::subscriber::with_default(subscriber, || {
+        let mut app = App::new();
+        app.add_plugins(RichTextPlugin::default());
+
+        let ent = app.world_mut().spawn(RichText::new("a[typo]b")).id();
+        app.update();
+
+        app.world_mut().get_mut::<RichText>(ent).unwrap().0 = "c[typo]d".into();
+        app.update();
+        assert_eq!(
+            app.world().get::<PreviousMarkup>(ent).unwrap().0,
+            "c[typo]d"
+        );
+    });
+
+    assert_eq!(warnings.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_visit_sections() {
+    let mut values = vec![];
+    RichText::new("a[red]b[]c").visit_sections(|section| values.push(section.value.clone()));
+
+    assert_eq!(values, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_as_plain_html() {
+    assert_eq!(
+        RichText::new("[red]Hello[] <World>[red,bold]!").as_plain_html(),
+        "<span class=\"red\">Hello</span> &lt;World&gt;<span class=\"red bold\">!</span>"
+    );
+}
+
+#[test]
+fn test_counts() {
+    let rt = RichText::new("[red]héllo[] [[x]]");
+
+    assert_eq!(rt.char_count(), 9);
+    assert_eq!(rt.byte_count(), 10);
+    assert_eq!(rt.len_plain(), 10);
+    assert_eq!(rt.len_markup(), 19);
+}
+
+#[test]
+fn test_from_plain() {
+    assert_eq!(RichText::from_plain("[red]").plain_text(), "[red]");
+    assert_eq!(RichText::from_plain("[red]").0, "[[red]]");
+}
+
+#[test]
+fn test_plain_text() {
+    assert_eq!(
+        RichText::new("[red]Hello[] World").plain_text(),
+        "Hello World"
+    );
+    assert_eq!(RichText2d::new("[[x]][red]]]").plain_text(), "[x]]");
+}
+
+#[test]
+fn test_sections_equal() {
+    assert!(RichText::sections_equal("[red][blue]text", "[blue]text"));
+    assert!(RichText::sections_equal("[]a[red]b", "a[red]b[]"));
+    assert!(!RichText::sections_equal(
+        "[red,blue]text",
+        "[blue,red]text"
+    ));
+}
+
+#[test]
+fn test_from_lines() {
+    let lines = vec![
+        (vec!["red".to_string()], "Hello".to_string()),
+        (vec![], "[World]".to_string()),
+    ];
+
+    assert_eq!(
+        RichText::from_lines(lines.clone()).0,
+        "[red]Hello\n[][[World]]"
+    );
+    assert_eq!(
+        RichText::from_paragraphs(lines).0,
+        "[red]Hello\n\n[][[World]]"
+    );
+}
+
+#[test]
+fn test_map_tags() {
+    let rt = RichText::new("[em]a[em,red]b[]c").map_tags(|tag| match tag {
+        "em" => Some("emphasis".to_string()),
+        "red" => None,
+        tag => Some(tag.to_string()),
+    });
+
+    assert_eq!(rt.0, "[emphasis]ab[]c");
+}
+
+#[test]
+fn test_validate_tags() {
+    let registry = StyleTags(HashMap::from([
+        ("".to_string(), Entity::PLACEHOLDER),
+        ("red".to_string(), Entity::PLACEHOLDER),
+    ]));
+
+    assert!(RichText::new("[red]a[]b")
+        .validate_tags(&registry)
+        .is_empty());
+    assert_eq!(
+        RichText::new("[bold]a[red,bold,blue]b").validate_tags(&registry),
+        vec!["bold".to_string(), "blue".to_string()]
+    );
+
+    // Built-in tags are always known.
+    assert!(
+        RichText::new("[quote]a[quote=\"Bob\"]b[loc=greeting]c[#ff0000,size=2]d")
+            .validate_tags(&registry)
+            .is_empty()
+    );
+
+    let groups = TagGroups(HashMap::from([(
+        "warning".to_string(),
+        Entity::PLACEHOLDER,
+    )]));
+    let local = LocalStyleTags(HashMap::from([("local".to_string(), Entity::PLACEHOLDER)]));
+    let inline = InlineStyle::new("inline");
+    let known = KnownTags::new(&registry)
+        .with_groups(&groups)
+        .with_local_tags(&local)
+        .with_inline_style(&inline);
+    assert!(RichText::new("[warning]a[local,inline]b")
+        .validate_tags(known)
+        .is_empty());
+    assert_eq!(
+        RichText::new("[RED]a").validate_tags(known),
+        vec!["RED".to_string()]
+    );
+    assert!(RichText::new("[RED]a")
+        .validate_tags(known.case_insensitive(true))
+        .is_empty());
+    assert!(RichText::new("[WARNING]a[Local,INLINE]b")
+        .validate_tags(known.case_insensitive(true))
+        .is_empty());
+}
+
+#[test]
+fn test_contains_tag() {
+    let rt = RichText::new("a[red,bold]b[red]c[blue]");
+
+    assert!(rt.contains_tag("red"));
+    assert!(rt.contains_tag("bold"));
+    assert!(!rt.contains_tag("blue"));
+    assert_eq!(
+        rt.active_tags(),
+        HashSet::from(["red".to_string(), "bold".to_string()])
+    );
+}
+
+#[test]
+fn test_tag_count() {
+    let rt = RichText::new("[shout]a[shout,red]b[]c[red]d");
+    assert!(rt.has_tag("shout"));
+    assert!(!rt.has_tag("blue"));
+    assert_eq!(rt.tag_count(), 2);
+
+    let rt = RichText::new("plain [[text]]");
+    assert!(!rt.has_tag(""));
+    assert_eq!(rt.tag_count(), 0);
+}
+
+#[test]
+fn test_section_at_char() {
+    let rt = RichText::new("ab[red]cd[]");
+
+    let (section, offset) = rt.section_at_char(1).unwrap();
+    assert_eq!((section.value.as_str(), offset), ("ab", 1));
+    let (section, offset) = rt.section_at_char(2).unwrap();
+    assert_eq!((section.value.as_str(), offset), ("cd", 0));
+    assert!(rt.section_at_char(4).is_none());
+}
+
+#[test]
+fn test_localize() {
+    let localization = LocalizationRegistry(HashMap::from([(
+        "ui.submit".to_string(),
+        "Envoyer".to_string(),
+    )]));
+
+    let mut sections = parse_richtext("[lg,loc=ui.submit]Submit[loc=missing]Cancel");
+    localization.localize(&mut sections);
+
+    assert_eq!(sections[0].value, "Envoyer");
+    assert_eq!(sections[0].tags, vec!["lg".to_string()]);
+    assert_eq!(sections[1].value, "Cancel");
+    assert!(sections[1].tags.is_empty());
+}
+
+#[test]
+fn test_concat() {
+    let parts = || [RichText::new("[red]a"), RichText::new("b")];
+
+    assert_eq!(concat(parts()).0, "[red]a[]b");
+    assert_eq!(
+        concat_with_separator(parts(), ", [x]").0,
+        "[red]a[], [[x]]b"
+    );
+    assert_eq!(
+        parse_richtext(&concat(parts()).0),
+        parse_richtext("[red]a[]b")
+    );
+}
+
+#[test]
+fn test_apply_transform() {
+    let rt = RichText::new("hello [red]wide wörld[]s");
+
+    assert_eq!(
+        rt.apply_transform(TextTransform::Uppercase).0,
+        "HELLO [red]WIDE WÖRLD[]S"
+    );
+    assert_eq!(
+        rt.apply_transform(TextTransform::Capitalize).0,
+        "Hello [red]Wide wörld[]S"
+    );
+    assert_eq!(
+        rt.apply_transform(TextTransform::TitleCase).0,
+        "Hello [red]Wide Wörld[]s"
+    );
+}
+
+#[test]
+fn test_format() {
+    let rt = RichText::new("{0} {1} {2} {x} {0");
+
+    assert_eq!(rt.format(&["a", "b"]).0, "a b {2} {x} {0");
+}
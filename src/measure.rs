@@ -0,0 +1,109 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Changed, With},
+        schedule::SystemSet,
+        system::{Commands, Query},
+    },
+    math::Vec2,
+    text::TextLayoutInfo,
+    ui::ComputedNode,
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{RichText, RichText2d};
+
+/// A system set in `PostUpdate` that writes [`RenderedTextSize`], after the
+/// text layout of `bevy_ui` and `Text2d` has been computed.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RichTextMeasure;
+
+/// A component added to each [`RichText`] and [`RichText2d`] holding the size of
+/// its rendered text in logical pixels.
+///
+/// This is written in `PostUpdate` after the text is laid out, so it is only
+/// valid one frame after the rich text is built or changed. Systems running
+/// in `PostUpdate` can read it in the same frame by running
+/// `.after(RichTextMeasure)`.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderedTextSize {
+    /// The width of the text.
+    pub width: f32,
+    /// The height of the text.
+    pub height: f32,
+}
+impl From<Vec2> for RenderedTextSize {
+    fn from(size: Vec2) -> Self {
+        Self {
+            width: size.x,
+            height: size.y,
+        }
+    }
+}
+
+pub(crate) fn measure_richtext(
+    mut commands: Commands,
+    query: Query<(Entity, &TextLayoutInfo, Option<&ComputedNode>), Changed<TextLayoutInfo>>,
+    rt_query: Query<(), With<RichText>>,
+    rt_2d_query: Query<(), With<RichText2d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    // `Text2d` is laid out at the scale factor of the primary window.
+    let scale_factor_2d = windows
+        .get_single()
+        .map_or(1., |window| window.resolution.scale_factor());
+
+    for (ent, layout, node) in &query {
+        let inverse_scale_factor = if rt_query.contains(ent) {
+            node.map_or(1., |node| node.inverse_scale_factor())
+        } else if rt_2d_query.contains(ent) {
+            scale_factor_2d.recip()
+        } else {
+            continue;
+        };
+
+        commands
+            .entity(ent)
+            .insert(RenderedTextSize::from(layout.size * inverse_scale_factor));
+    }
+}
+
+#[test]
+fn test_rendered_text_size() {
+    use bevy::app::App;
+
+    use crate::RichTextPlugin;
+
+    let mut app = App::new();
+    app.add_plugins(RichTextPlugin::default());
+
+    let ui = app
+        .world_mut()
+        .spawn((
+            RichText::new("a"),
+            TextLayoutInfo {
+                size: Vec2::new(20., 10.),
+                ..Default::default()
+            },
+        ))
+        .id();
+    let other = app
+        .world_mut()
+        .spawn(TextLayoutInfo {
+            size: Vec2::new(20., 10.),
+            ..Default::default()
+        })
+        .id();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(
+        world.get::<RenderedTextSize>(ui),
+        Some(&RenderedTextSize {
+            width: 20.,
+            height: 10.
+        })
+    );
+    assert!(world.get::<RenderedTextSize>(other).is_none());
+}
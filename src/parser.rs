@@ -1,72 +1,312 @@
+use std::{fmt, ops::Range};
+
+#[cfg(test)]
+use crate::assert_rich_text_sections;
+use bevy::color::{Color, Srgba};
 use chumsky::{
     error::Cheap,
-    primitive::{choice, just, none_of},
+    primitive::{choice, end, just, none_of},
     Parser,
 };
 
-#[derive(Default)]
-pub(crate) struct TextSection {
-    pub(crate) value: String,
-    pub(crate) tags: Vec<String>,
+/// A run of text and the tags that apply to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextSection {
+    /// The text, with any escaped brackets unescaped.
+    pub value: String,
+    /// The tags that apply to the text.
+    pub tags: Vec<String>,
+}
+
+/// An error describing malformed markup, produced by
+/// [`parse_richtext_errors`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte range of the input where the error occurred.
+    pub span: Range<usize>,
+    /// A description of the error.
+    pub message: String,
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {:?}", self.message, self.span)
+    }
 }
+impl std::error::Error for ParseError {}
 
 #[derive(Debug, PartialEq, Eq)]
 enum TagsOrText {
     Tags(Vec<String>),
+    /// A closing tag like `[/tag]`, or `[/]` which closes every tag.
+    Close(Option<String>),
     Text(String),
 }
 
-fn escaped_bracket() -> impl Parser<char, String, Error = Cheap<char>> {
-    just('[')
-        .ignore_then(just('['))
-        .or(just(']').ignore_then(just(']')))
+/// The characters that delimit tag blocks and separate tags within them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Delimiters {
+    pub(crate) open: char,
+    pub(crate) close: char,
+    pub(crate) separator: char,
+}
+impl Default for Delimiters {
+    fn default() -> Self {
+        Self {
+            open: '[',
+            close: ']',
+            separator: ',',
+        }
+    }
+}
+
+fn escaped_bracket(d: Delimiters) -> impl Parser<char, String, Error = Cheap<char>> {
+    just(d.open)
+        .ignore_then(just(d.open))
+        .or(just(d.close).ignore_then(just(d.close)))
         .map(|c| c.to_string())
 }
 
-fn tag_block() -> impl Parser<char, TagsOrText, Error = Cheap<char>> {
-    tags()
-        .delimited_by(just('['), just(']'))
+fn tag_block(d: Delimiters) -> impl Parser<char, TagsOrText, Error = Cheap<char>> {
+    tags(d)
+        .delimited_by(just(d.open), just(d.close))
         .map(TagsOrText::Tags)
 }
 
-fn tags() -> impl Parser<char, Vec<String>, Error = Cheap<char>> {
-    not_end_bracket_or_comma()
-        .separated_by(just(','))
-        .collect::<Vec<_>>()
+fn close_block(d: Delimiters) -> impl Parser<char, TagsOrText, Error = Cheap<char>> {
+    just(d.open)
+        .ignore_then(just('/'))
+        .ignore_then(
+            none_of([d.close, d.separator])
+                .repeated()
+                .collect::<String>(),
+        )
+        .then_ignore(just(d.close))
+        .map(|tag| {
+            tag.trim_matches(|c: char| c.is_ascii_whitespace())
+                .to_string()
+        })
+        .map(|tag| TagsOrText::Close(Some(tag).filter(|tag| !tag.is_empty())))
 }
 
-fn not_end_bracket_or_comma() -> impl Parser<char, String, Error = Cheap<char>> {
-    none_of("],").repeated().at_least(1).collect::<String>()
+fn tags(d: Delimiters) -> impl Parser<char, Vec<String>, Error = Cheap<char>> {
+    not_end_bracket_or_comma(d)
+        .separated_by(just(d.separator))
+        .map(|tags| tags.into_iter().filter(|tag| !tag.is_empty()).collect())
 }
 
-fn not_any_bracket() -> impl Parser<char, String, Error = Cheap<char>> {
-    none_of("[]").repeated().at_least(1).collect::<String>()
+/// A single tag, with any surrounding whitespace trimmed.
+fn not_end_bracket_or_comma(d: Delimiters) -> impl Parser<char, String, Error = Cheap<char>> {
+    none_of([d.close, d.separator])
+        .repeated()
+        .at_least(1)
+        .collect::<String>()
+        .map(|tag| {
+            tag.trim_matches(|c: char| c.is_ascii_whitespace())
+                .to_string()
+        })
 }
 
-fn stray_end_bracket() -> impl Parser<char, String, Error = Cheap<char>> {
-    just(']').map(|c| c.to_string())
+fn not_any_bracket(d: Delimiters) -> impl Parser<char, String, Error = Cheap<char>> {
+    none_of([d.open, d.close])
+        .repeated()
+        .at_least(1)
+        .collect::<String>()
+}
+
+fn stray_end_bracket(d: Delimiters) -> impl Parser<char, String, Error = Cheap<char>> {
+    just(d.close).map(|c| c.to_string())
 }
 
-fn text() -> impl Parser<char, TagsOrText, Error = Cheap<char>> {
-    choice((escaped_bracket(), not_any_bracket(), stray_end_bracket()))
+fn text(d: Delimiters) -> impl Parser<char, TagsOrText, Error = Cheap<char>> {
+    choice((escaped_bracket(d), not_any_bracket(d), stray_end_bracket(d)))
         .repeated()
         .at_least(1)
         .collect::<String>()
         .map(TagsOrText::Text)
 }
 
-fn tags_or_text() -> impl Parser<char, Vec<TagsOrText>, Error = Cheap<char>> {
-    choice((text(), tag_block())).repeated().collect::<Vec<_>>()
+fn tags_or_text(d: Delimiters) -> impl Parser<char, Vec<TagsOrText>, Error = Cheap<char>> {
+    choice((text(d), close_block(d), tag_block(d)))
+        .repeated()
+        .collect::<Vec<_>>()
 }
 
+/// The indentation inserted before quoted text. `TextSpan`s can't have margins
+/// or padding, so non-breaking spaces are used instead.
+const QUOTE_INDENT: &str = "\u{a0}\u{a0}\u{a0}\u{a0}";
+
+/// If `tag` is the built-in `quote` tag, returns the text that should be
+/// inserted before the quoted text.
+fn quote_prefix(tag: &str) -> Option<String> {
+    if tag == "quote" {
+        return Some(QUOTE_INDENT.to_string());
+    }
+
+    let source = tag.strip_prefix("quote=")?.trim_matches('"');
+
+    Some(format!("{QUOTE_INDENT}\"{source}\" says: "))
+}
+
+/// Returns `true` if `tag` is handled by the parser or styler itself rather
+/// than by a registered style: the default style `""`, inline tags like
+/// `#ff0000`, `quote`, `quote="source"` and `loc=key`.
+pub(crate) fn is_builtin_tag(tag: &str) -> bool {
+    tag.is_empty()
+        || quote_prefix(tag).is_some()
+        || tag.starts_with("loc=")
+        || inline_tag(tag).is_some()
+}
+
+/// Inserts the text of the built-in `quote` tag at the start of each quote, and
+/// replaces any `quote="source"` tags with `quote` so that they are styled by
+/// the `quote` style.
+///
+/// A quote starts at any section with a quote tag that the previous section
+/// didn't have.
+pub(crate) fn apply_quote_prefixes(sections: &mut [TextSection]) {
+    let mut previous_quote = None;
+
+    for section in sections {
+        let quote = section
+            .tags
+            .iter()
+            .rev()
+            .find_map(|tag| Some((tag.clone(), quote_prefix(tag)?)));
+
+        if let Some((tag, prefix)) = &quote {
+            if previous_quote.as_ref() != Some(tag) {
+                section.value.insert_str(0, prefix);
+            }
+            for tag in &mut section.tags {
+                if quote_prefix(tag).is_some() {
+                    "quote".clone_into(tag);
+                }
+            }
+        }
+
+        previous_quote = quote.map(|(tag, _)| tag);
+    }
+}
+
+/// A built-in tag that styles text directly, without a registered
+/// [`StyleTag`](crate::StyleTag).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum InlineTag {
+    /// `[#rgb]`, `[#rrggbb]`, or `[#rrggbbaa]`.
+    Color(Color),
+    /// `[size=N]`, where `N` is a non-negative font size.
+    FontSize(f32),
+    /// `[alpha=N]`, where `N` is between `0` and `1`. Scales the alpha of the
+    /// span's color.
+    Alpha(f32),
+    /// `[font=path]`, where `path` is the asset path of a font.
+    Font(String),
+}
+
+/// If `tag` is a built-in inline tag, returns the style it applies.
+pub(crate) fn inline_tag(tag: &str) -> Option<InlineTag> {
+    if let Some(hex) = tag.strip_prefix('#') {
+        return Srgba::hex(hex)
+            .ok()
+            .map(|color| InlineTag::Color(color.into()));
+    }
+
+    if let Some(size) = tag.strip_prefix("size=") {
+        return size
+            .parse::<f32>()
+            .ok()
+            .filter(|size| size.is_finite() && *size >= 0.)
+            .map(InlineTag::FontSize);
+    }
+
+    if let Some(alpha) = tag.strip_prefix("alpha=") {
+        return alpha
+            .parse::<f32>()
+            .ok()
+            .filter(|alpha| (0. ..=1.).contains(alpha))
+            .map(InlineTag::Alpha);
+    }
+
+    if let Some(path) = tag.strip_prefix("font=") {
+        return Some(path)
+            .filter(|path| !path.is_empty())
+            .map(|path| InlineTag::Font(path.to_string()));
+    }
+
+    None
+}
+
+/// Removes the most recently opened `tag` from `current_tags`, or all tags if
+/// `tag` is `None`.
+fn close_tag(current_tags: &mut Vec<String>, tag: Option<String>) {
+    let Some(tag) = tag else {
+        current_tags.clear();
+        return;
+    };
+
+    // `[/quote]` also closes `[quote="source"]`.
+    let is_quote = tag == "quote";
+    match current_tags
+        .iter()
+        .rposition(|current| *current == tag || (is_quote && quote_prefix(current).is_some()))
+    {
+        Some(index) => {
+            current_tags.remove(index);
+        }
+        None => bevy::log::warn!("Closing tag `[/{}]` does not match any open tag.", tag),
+    }
+}
+
+/// Parses `text` into sections, falling back to a single empty section if the
+/// input can't be parsed. Same as [`parse_richtext_permissive`].
+///
+/// Whitespace is preserved as-is. Input without any text, such as `""` or
+/// `"[red][blue]"`, produces a single empty section with no tags.
+///
+/// ```
+/// # use bevy_simple_rich_text::parse_richtext;
+/// let sections = parse_richtext("[bold]Hello");
+///
+/// assert_eq!(sections[0].value, "Hello");
+/// assert_eq!(sections[0].tags, vec!["bold".to_string()]);
+/// ```
 pub fn parse_richtext(text: &str) -> Vec<TextSection> {
+    parse_richtext_permissive(text)
+}
+
+/// Parses `text` into sections, never failing.
+///
+/// Malformed input is logged, and results in a single empty section.
+pub fn parse_richtext_permissive(text: &str) -> Vec<TextSection> {
+    parse_richtext_permissive_with(text, Delimiters::default())
+}
+
+/// Like [`parse_richtext_permissive`], but with custom delimiters.
+pub(crate) fn parse_richtext_permissive_with(text: &str, d: Delimiters) -> Vec<TextSection> {
     let mut sections = vec![];
-    let mut current_tags = vec![];
+    parse_richtext_callback_with(text, d, |section| sections.push(section));
+    sections
+}
+
+/// Parses `text`, returning only the visible text without any markup.
+pub fn parse_richtext_to_plain(text: &str) -> String {
+    let mut plain = String::new();
+    parse_richtext_callback(text, |section| plain.push_str(&section.value));
+    plain
+}
 
-    let result = tags_or_text().parse(text);
+/// Parses `text`, calling `callback` with each section as it is produced rather
+/// than collecting them into a `Vec`.
+///
+/// Like [`parse_richtext_permissive`], this never fails.
+pub fn parse_richtext_callback(text: &str, callback: impl FnMut(TextSection)) {
+    parse_richtext_callback_with(text, Delimiters::default(), callback);
+}
 
-    let tags_or_text = match result {
-        Ok(tags_or_text) => tags_or_text,
+fn parse_richtext_callback_with(text: &str, d: Delimiters, mut callback: impl FnMut(TextSection)) {
+    match tags_or_text(d).parse(text) {
+        Ok(tags_or_text) => visit_sections(tags_or_text, callback),
         Err(errors) => {
             bevy::log::error!(
                 "bevy_simple_rich_text failed to parse the input string. This should never happen."
@@ -80,47 +320,298 @@ pub fn parse_richtext(text: &str) -> Vec<TextSection> {
                 );
             }
 
-            sections.push(TextSection {
-                value: "".to_string(),
-                tags: current_tags,
-            });
-
-            return sections;
+            callback(TextSection::default());
         }
-    };
+    }
+}
+
+/// Parses `text` into sections, also returning a list of any problems with the
+/// markup.
+///
+/// The sections are the same as those returned by [`parse_richtext`], so this
+/// is useful for tooling that wants to report malformed markup to content
+/// creators.
+///
+/// ```
+/// # use bevy_simple_rich_text::parse_richtext_errors;
+/// let (_, errors) = parse_richtext_errors("[red]text[]");
+/// assert!(errors.is_empty());
+///
+/// let (sections, errors) = parse_richtext_errors("[red");
+/// assert_eq!(sections.len(), 1);
+/// assert_eq!(errors[0].span, 4..4);
+/// ```
+pub fn parse_richtext_errors(text: &str) -> (Vec<TextSection>, Vec<ParseError>) {
+    match parse_richtext_all_errors_with(text, Delimiters::default()) {
+        Ok(sections) => (sections, vec![]),
+        Err(errors) => (parse_richtext_permissive(text), errors),
+    }
+}
+
+/// Parses `text` into sections, returning an error if any part of the input is
+/// malformed rather than silently ignoring it.
+///
+/// This is the parser used when [`RichTextConfig::strict`] is enabled.
+///
+/// [`RichTextConfig::strict`]: crate::RichTextConfig::strict
+///
+/// ```
+/// # use bevy_simple_rich_text::parse_richtext_strict;
+/// assert!(parse_richtext_strict("[red]text[]").is_ok());
+/// assert_eq!(parse_richtext_strict("[red").unwrap_err().span, 4..4);
+/// ```
+pub fn parse_richtext_strict(text: &str) -> Result<Vec<TextSection>, ParseError> {
+    parse_richtext_strict_with(text, Delimiters::default())
+}
+
+/// Like [`parse_richtext_strict`], but with custom delimiters.
+pub(crate) fn parse_richtext_strict_with(
+    text: &str,
+    d: Delimiters,
+) -> Result<Vec<TextSection>, ParseError> {
+    parse_richtext_all_errors_with(text, d).map_err(|mut errors| errors.remove(0))
+}
+
+fn parse_richtext_all_errors_with(
+    text: &str,
+    d: Delimiters,
+) -> Result<Vec<TextSection>, Vec<ParseError>> {
+    tags_or_text(d)
+        .then_ignore(end())
+        .parse(text)
+        .map(|tags_or_text| {
+            let mut sections = vec![];
+            visit_sections(tags_or_text, |section| sections.push(section));
+            sections
+        })
+        .map_err(|errors| {
+            let mut errors = errors
+                .into_iter()
+                .map(|error| {
+                    let span = error.span();
+                    let message = match text.get(span.clone()) {
+                        Some(found) if !found.is_empty() => format!("unexpected `{found}`"),
+                        _ => "unexpected end of input".to_string(),
+                    };
+                    ParseError { span, message }
+                })
+                .collect::<Vec<_>>();
+            if errors.is_empty() {
+                errors.push(ParseError {
+                    span: 0..text.len(),
+                    message: "unexpected input".to_string(),
+                });
+            }
+            errors
+        })
+}
+
+fn visit_sections(tags_or_text: Vec<TagsOrText>, mut callback: impl FnMut(TextSection)) {
+    let mut empty = true;
+    let mut current_tags = vec![];
 
     for t in tags_or_text {
         match t {
-            TagsOrText::Text(value) => sections.push(TextSection {
-                value,
-                tags: current_tags.clone(),
-            }),
+            TagsOrText::Text(value) => {
+                empty = false;
+                callback(TextSection {
+                    value,
+                    tags: current_tags.clone(),
+                });
+            }
             TagsOrText::Tags(tags) => current_tags = tags,
+            TagsOrText::Close(tag) => close_tag(&mut current_tags, tag),
         }
     }
 
-    if sections.is_empty() {
-        sections.push(TextSection {
-            value: "".to_string(),
-            tags: vec![],
-        });
+    if empty {
+        callback(TextSection::default());
     }
+}
 
-    sections
+/// Collapses runs of whitespace into a single space, and removes leading and
+/// trailing whitespace, like HTML does. Runs that span multiple sections are
+/// collapsed into the earliest section.
+pub(crate) fn collapse_whitespace(sections: &mut [TextSection]) {
+    let mut last_was_whitespace = true;
+
+    for section in sections.iter_mut() {
+        let mut value = String::with_capacity(section.value.len());
+
+        for c in section.value.chars() {
+            // Like in HTML, non-breaking spaces are preserved.
+            if c.is_whitespace() && c != '\u{a0}' {
+                if !last_was_whitespace {
+                    value.push(' ');
+                }
+                last_was_whitespace = true;
+            } else {
+                value.push(c);
+                last_was_whitespace = false;
+            }
+        }
+
+        section.value = value;
+    }
+
+    if let Some(section) = sections
+        .iter_mut()
+        .rev()
+        .find(|section| !section.value.is_empty())
+    {
+        let len = section
+            .value
+            .trim_end_matches(|c: char| c.is_whitespace() && c != '\u{a0}')
+            .len();
+        section.value.truncate(len);
+    }
+}
+
+/// A token produced by [`RichTextScanner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanToken {
+    /// A run of text, with any escaped brackets unescaped.
+    Text(String),
+    /// A tag block, which replaces the active tags, or a closing tag. Contains
+    /// the new active tags.
+    TagChange(Vec<String>),
+}
+
+/// An iterator over the [`ScanToken`]s of some markup, from left to right.
+///
+/// Created by [`RichText::scan`](crate::RichText::scan).
+pub struct RichTextScanner {
+    tokens: std::vec::IntoIter<TagsOrText>,
+    active_tags: Vec<String>,
+}
+impl RichTextScanner {
+    /// Creates a new [`RichTextScanner`] for `text`. Malformed input produces
+    /// no tokens.
+    pub fn new(text: &str) -> Self {
+        Self {
+            tokens: tags_or_text(Delimiters::default())
+                .parse(text)
+                .unwrap_or_default()
+                .into_iter(),
+            active_tags: vec![],
+        }
+    }
+
+    /// The tags that are active at the scanner's current position.
+    pub fn active_tags(&self) -> &[String] {
+        &self.active_tags
+    }
+}
+impl Iterator for RichTextScanner {
+    type Item = ScanToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.tokens.next()? {
+            TagsOrText::Text(value) => Some(ScanToken::Text(value)),
+            TagsOrText::Tags(tags) => {
+                self.active_tags.clone_from(&tags);
+                Some(ScanToken::TagChange(tags))
+            }
+            TagsOrText::Close(tag) => {
+                close_tag(&mut self.active_tags, tag);
+                Some(ScanToken::TagChange(self.active_tags.clone()))
+            }
+        }
+    }
+}
+
+/// Escapes any brackets in `text` so that it is displayed literally when
+/// embedded in markup.
+///
+/// Use this for any untrusted text, like player names or chat messages, to
+/// prevent it from injecting tags.
+///
+/// This escapes the default `[` and `]` delimiters. When
+/// [`RichTextConfig`](crate::RichTextConfig) uses custom delimiters, use
+/// [`RichTextConfig::escape_markup`](crate::RichTextConfig::escape_markup)
+/// instead, or the text will still be able to inject tags.
+pub fn escape_markup(text: &str) -> String {
+    escape_markup_with(text, Delimiters::default())
+}
+
+/// Like [`escape_markup`], but with custom delimiters.
+pub(crate) fn escape_markup_with(text: &str, d: Delimiters) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == d.open || c == d.close {
+            escaped.push(c);
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Reverses [`escape_markup`].
+///
+/// Like [`escape_markup`], this only handles the default `[` and `]`
+/// delimiters.
+pub fn unescape_markup(text: &str) -> String {
+    text.replace("[[", "[").replace("]]", "]")
+}
+
+/// Converts the escape sequences `\n` and `\\` in `markup` into a newline
+/// and a backslash. Any other backslashes are kept as-is.
+///
+/// This is useful for markup that comes from formats where a literal `\n` is
+/// commonly written instead of a newline.
+pub fn process_escape_sequences(markup: &str) -> String {
+    let mut processed = String::with_capacity(markup.len());
+    let mut chars = markup.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('n')) => {
+                processed.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                processed.push('\\');
+                chars.next();
+            }
+            _ => processed.push(c),
+        }
+    }
+
+    processed
+}
+
+/// Builds a markup string that parses into `sections`, using the default `[`
+/// and `]` delimiters.
+pub fn to_markup(sections: impl IntoIterator<Item = TextSection>) -> String {
+    let mut markup = String::new();
+    let mut current_tags = vec![];
+
+    for section in sections {
+        if section.tags != current_tags {
+            markup.push('[');
+            markup.push_str(&section.tags.join(","));
+            markup.push(']');
+            current_tags = section.tags;
+        }
+
+        markup.push_str(&escape_markup(&section.value));
+    }
+
+    markup
 }
 
 #[test]
 fn test_parser() {
     assert_eq!(
-        tags_or_text().parse("[bold]"),
+        tags_or_text(Delimiters::default()).parse("[bold]"),
         Ok(vec![TagsOrText::Tags(vec!["bold".to_string()])])
     );
     assert_eq!(
-        tags_or_text().parse("[[horse]]"),
+        tags_or_text(Delimiters::default()).parse("[[horse]]"),
         Ok(vec![TagsOrText::Text("[horse]".to_string())])
     );
     assert_eq!(
-        tags_or_text().parse("[bold]Bold Text[italic]Italic Text"),
+        tags_or_text(Delimiters::default()).parse("[bold]Bold Text[italic]Italic Text"),
         Ok(vec![
             TagsOrText::Tags(vec!["bold".to_string()]),
             TagsOrText::Text("Bold Text".to_string()),
@@ -129,7 +620,7 @@ fn test_parser() {
         ])
     );
     assert_eq!(
-        tags_or_text().parse("[]Text[]"),
+        tags_or_text(Delimiters::default()).parse("[]Text[]"),
         Ok(vec![
             TagsOrText::Tags(vec![]),
             TagsOrText::Text("Text".to_string()),
@@ -138,7 +629,7 @@ fn test_parser() {
     );
     // escaping
     assert_eq!(
-        tags_or_text().parse("[[]]][]"),
+        tags_or_text(Delimiters::default()).parse("[[]]][]"),
         Ok(vec![
             TagsOrText::Text("[]]".to_string()),
             TagsOrText::Tags(vec![]),
@@ -146,7 +637,7 @@ fn test_parser() {
     );
     // multiple
     assert_eq!(
-        tags_or_text().parse("[bold,italic]text"),
+        tags_or_text(Delimiters::default()).parse("[bold,italic]text"),
         Ok(vec![
             TagsOrText::Tags(vec!["bold".to_string(), "italic".to_string()]),
             TagsOrText::Text("text".to_string()),
@@ -156,27 +647,320 @@ fn test_parser() {
 
 #[test]
 fn test_empty() {
-    let sections = parse_richtext("");
+    assert_rich_text_sections!("", [("", [])]);
+}
+
+#[test]
+fn test_sections() {
+    assert_rich_text_sections!(
+        "test1[red]test2[]test3[red,bold]test4",
+        [
+            ("test1", []),
+            ("test2", ["red"]),
+            ("test3", []),
+            ("test4", ["red", "bold"]),
+        ]
+    );
+}
+
+#[test]
+fn test_quote() {
+    assert_rich_text_sections!(
+        "[quote]a[quote=\"Bob\",red]b[]c",
+        [("a", ["quote"]), ("b", ["quote=\"Bob\"", "red"]), ("c", [])]
+    );
+
+    let mut sections = parse_richtext("[quote]a[quote=\"Bob\",red]b[/red]c[]d[quote]e");
+    apply_quote_prefixes(&mut sections);
+    let sections = sections
+        .into_iter()
+        .map(|section| (section.value, section.tags))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        vec![
+            (format!("{QUOTE_INDENT}a"), vec!["quote".to_string()]),
+            (
+                format!("{QUOTE_INDENT}\"Bob\" says: b"),
+                vec!["quote".to_string(), "red".to_string()]
+            ),
+            ("c".to_string(), vec!["quote".to_string()]),
+            ("d".to_string(), vec![]),
+            (format!("{QUOTE_INDENT}e"), vec!["quote".to_string()]),
+        ]
+    );
+
+    // `[/quote]` closes a quote with a source.
+    assert_rich_text_sections!(
+        "[quote=\"Bob\",red]a[/quote]b",
+        [("a", ["quote=\"Bob\"", "red"]), ("b", ["red"])]
+    );
+}
+
+#[test]
+fn test_quote_round_trip() {
+    for markup in ["[quote]a", "[quote=\"Bob\",red]b[]c", "x[quote]y[]z"] {
+        assert_eq!(to_markup(parse_richtext(markup)), markup);
+        assert_eq!(
+            parse_richtext_to_plain(markup),
+            parse_richtext_to_plain(&to_markup(parse_richtext(markup)))
+        );
+    }
+    assert_eq!(parse_richtext_to_plain("[quote]a"), "a");
+}
 
+#[test]
+fn test_to_markup() {
+    let markup = "test1[red]test2[]test3[red,bold]test4[[]]";
+
+    assert_eq!(to_markup(parse_richtext(markup)), markup);
+}
+
+#[test]
+fn test_parse_errors() {
+    let (sections, errors) = parse_richtext_errors("a[red]b[]");
+    assert_eq!(sections, parse_richtext("a[red]b[]"));
+    assert!(errors.is_empty());
+
+    let (sections, errors) = parse_richtext_errors("a[red");
+    assert_eq!(sections, parse_richtext("a[red"));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "unexpected end of input");
+}
+
+#[test]
+fn test_strict() {
+    assert!(parse_richtext_strict_with("[red]text[]", Delimiters::default()).is_ok());
+    assert!(parse_richtext_strict_with("[red", Delimiters::default()).is_err());
+    assert_eq!(parse_richtext_permissive("[red").len(), 1);
+}
+
+#[test]
+fn test_collapse_whitespace() {
+    let mut sections = parse_richtext("  Hello  World ");
+    collapse_whitespace(&mut sections);
+    assert_eq!(sections[0].value, "Hello World");
+
+    let mut sections = parse_richtext("[red]Hello \n[blue] World[]\t");
+    collapse_whitespace(&mut sections);
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].value, "Hello ");
+    assert_eq!(sections[1].value, "World");
+    assert_eq!(sections[2].value, "");
+}
+
+#[test]
+fn test_escape_sequences() {
+    assert_eq!(
+        process_escape_sequences(r"[red]Hello\nWorld"),
+        "[red]Hello\nWorld"
+    );
+    assert_eq!(process_escape_sequences(r"a\\nb\tc\"), r"a\nb\tc\");
+    assert_eq!(process_escape_sequences("a\nb"), "a\nb");
+}
+
+#[test]
+fn test_to_plain() {
+    assert_eq!(
+        parse_richtext_to_plain("[red]Hello[] [[World]]"),
+        "Hello [World]"
+    );
+}
+
+#[test]
+fn test_whitespace_only() {
+    for input in ["   ", "\t\n\r", "\n\n", "\u{3000}\u{a0}"] {
+        assert_rich_text_sections!(input, [(input, [])]);
+    }
+
+    let mut sections = parse_richtext(" \t\n ");
+    collapse_whitespace(&mut sections);
     assert_eq!(sections.len(), 1);
     assert_eq!(sections[0].value, "");
 }
 
 #[test]
-fn test_sections() {
-    let sections = parse_richtext("test1[red]test2[]test3[red,bold]test4");
+fn test_tags_only() {
+    assert_rich_text_sections!("[red][blue]", [("", [])]);
+}
 
-    assert_eq!(sections.len(), 4);
+#[test]
+fn test_scanner() {
+    let mut scanner = RichTextScanner::new("a[red]b[[");
+
+    assert_eq!(scanner.next(), Some(ScanToken::Text("a".to_string())));
+    assert!(scanner.active_tags().is_empty());
+    assert_eq!(
+        scanner.next(),
+        Some(ScanToken::TagChange(vec!["red".to_string()]))
+    );
+    assert_eq!(scanner.active_tags(), ["red".to_string()]);
+    assert_eq!(scanner.next(), Some(ScanToken::Text("b[".to_string())));
+    assert_eq!(scanner.next(), None);
+}
+
+#[test]
+fn test_escape_markup() {
+    let text = "[red]Player]] [[";
+    let escaped = escape_markup(text);
+
+    assert_eq!(escaped, "[[red]]Player]]]] [[[[");
+    assert_eq!(parse_richtext_to_plain(&escaped), text);
+    assert_eq!(unescape_markup(&escaped), text);
+}
+
+#[test]
+fn test_closing_tags() {
+    assert_rich_text_sections!(
+        "[bold]a[bold,italic]b[/italic]c[/bold]d[bold,italic]e[/]f",
+        [
+            ("a", ["bold"]),
+            ("b", ["bold", "italic"]),
+            ("c", ["bold"]),
+            ("d", []),
+            ("e", ["bold", "italic"]),
+            ("f", []),
+        ]
+    );
+
+    // Only the most recent duplicate is closed.
+    assert_rich_text_sections!("[a,b,a]x[/a]y", [("x", ["a", "b", "a"]), ("y", ["a", "b"])]);
+
+    // Mismatched closing tags are ignored.
+    assert_rich_text_sections!("[a]x[/b]y", [("x", ["a"]), ("y", ["a"])]);
+}
+
+#[test]
+fn test_inline_tag() {
+    assert_eq!(
+        inline_tag("#f00"),
+        Some(InlineTag::Color(Color::srgb(1., 0., 0.)))
+    );
+    assert_eq!(
+        inline_tag("#00ff00"),
+        Some(InlineTag::Color(Color::srgb(0., 1., 0.)))
+    );
+    assert_eq!(
+        inline_tag("#0000ff00"),
+        Some(InlineTag::Color(Color::srgba(0., 0., 1., 0.)))
+    );
+    assert_eq!(inline_tag("#nope"), None);
+    assert_eq!(inline_tag("red"), None);
+
+    assert_eq!(inline_tag("size=24"), Some(InlineTag::FontSize(24.)));
+    assert_eq!(inline_tag("size=0"), Some(InlineTag::FontSize(0.)));
+    assert_eq!(inline_tag("size=-1"), None);
+    assert_eq!(inline_tag("size=abc"), None);
+
+    assert_eq!(inline_tag("alpha=0.5"), Some(InlineTag::Alpha(0.5)));
+    assert_eq!(inline_tag("alpha=1"), Some(InlineTag::Alpha(1.)));
+    assert_eq!(inline_tag("alpha=2"), None);
+    assert_eq!(inline_tag("alpha=NaN"), None);
+
+    assert_eq!(
+        inline_tag("font=fonts/bold.ttf"),
+        Some(InlineTag::Font("fonts/bold.ttf".to_string()))
+    );
+    assert_eq!(inline_tag("font="), None);
+
+    assert_rich_text_sections!("[size=24]text", [("text", ["size=24"])]);
+    assert_rich_text_sections!("[#ff0000,bold]text", [("text", ["#ff0000", "bold"])]);
+}
 
-    assert_eq!(sections[0].value, "test1");
-    assert_eq!(sections[0].tags, Vec::<String>::new());
-    assert_eq!(sections[1].value, "test2");
-    assert_eq!(sections[1].tags, vec!["red".to_string()]);
-    assert_eq!(sections[2].value, "test3");
-    assert_eq!(sections[2].tags, Vec::<String>::new());
-    assert_eq!(sections[3].value, "test4");
+#[test]
+fn test_delimiters() {
+    let braces = Delimiters {
+        open: '{',
+        close: '}',
+        separator: ',',
+    };
+    let angles = Delimiters {
+        open: '<',
+        close: '>',
+        separator: ';',
+    };
+
+    assert_eq!(
+        parse_richtext_permissive_with("[x]{red,lg}text{/lg}{{a}}", braces),
+        vec![
+            TextSection {
+                value: "[x]".to_string(),
+                tags: vec![],
+            },
+            TextSection {
+                value: "text".to_string(),
+                tags: vec!["red".to_string(), "lg".to_string()],
+            },
+            TextSection {
+                value: "{a}".to_string(),
+                tags: vec!["red".to_string()],
+            },
+        ]
+    );
+    assert_eq!(
+        parse_richtext_strict_with("<red;lg>text<>a,b", angles),
+        Ok(vec![
+            TextSection {
+                value: "text".to_string(),
+                tags: vec!["red".to_string(), "lg".to_string()],
+            },
+            TextSection {
+                value: "a,b".to_string(),
+                tags: vec![],
+            },
+        ])
+    );
+}
+
+#[test]
+fn test_tag_separator() {
+    let pipe = Delimiters {
+        separator: '|',
+        ..Default::default()
+    };
+    let space = Delimiters {
+        separator: ' ',
+        ..Default::default()
+    };
+
+    let sections = parse_richtext_permissive_with("[bold|italic]a, b[/italic]c", pipe);
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].value, "a, b");
+    assert_eq!(sections[0].tags, vec!["bold", "italic"]);
+    assert_eq!(sections[1].value, "c");
+    assert_eq!(sections[1].tags, vec!["bold"]);
+
+    // Commas are part of the tag name when they aren't the separator.
+    assert_eq!(
+        parse_richtext_strict_with("[ a,b | c ]x", pipe).map(|sections| sections[0].tags.clone()),
+        Ok(vec!["a,b".to_string(), "c".to_string()])
+    );
+
+    assert_eq!(
+        parse_richtext_strict_with("[bold italic]x", space)
+            .map(|sections| sections[0].tags.clone()),
+        Ok(vec!["bold".to_string(), "italic".to_string()])
+    );
+}
+
+#[test]
+fn test_tag_trimming() {
+    let parse = |text| tags_or_text(Delimiters::default()).parse(text);
+
+    assert_eq!(
+        parse("[ red ]"),
+        Ok(vec![TagsOrText::Tags(vec!["red".to_string()])])
+    );
+    assert_eq!(
+        parse("[bold , italic]"),
+        Ok(vec![TagsOrText::Tags(vec![
+            "bold".to_string(),
+            "italic".to_string()
+        ])])
+    );
+    assert_eq!(parse("[ ]"), parse("[]"));
     assert_eq!(
-        sections[3].tags,
-        vec!["red".to_string(), "bold".to_string()]
+        parse("[/ red ]"),
+        Ok(vec![TagsOrText::Close(Some("red".to_string()))])
     );
 }
@@ -0,0 +1,365 @@
+//! Data-driven style tags loaded from RON files.
+
+use std::fmt;
+
+use bevy::{
+    app::{App, Plugin},
+    asset::{io::Reader, Asset, AssetApp, AssetEvent, AssetId, AssetLoader, AssetServer, Assets},
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        system::{Commands, Query, Res, ResMut},
+    },
+    prelude::IntoSystemConfigs,
+    reflect::TypePath,
+    text::{TextColor, TextFont},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{RichTextSchedule, RichTextSystems, StyleTag, StyleTagUsers};
+
+/// A plugin that loads [`StyleTagConfig`]s from `.richstyle` and
+/// `.richtags.ron` files and spawns a [`StyleTag`] entity for each of their
+/// entries.
+///
+/// Keep a `Handle<StyleTagConfig>` around for as long as the tags should
+/// exist. When the file is modified (with Bevy's `file_watcher` feature), the
+/// style entities of tags that are still defined are updated in place, so any
+/// [`Aliases`](crate::Aliases) or other components added to them are kept, and
+/// the rich text using them is restyled.
+///
+/// ```ron
+/// (
+///     tags: {
+///         "red": (color: Some("#ff0000")),
+///         "lg": (font_size: Some(40.0)),
+///         "fancy": (font_path: Some("fonts/FiraMono-Medium.ttf")),
+///     },
+/// )
+/// ```
+///
+/// Requires the `style_config` feature.
+pub struct StyleTagConfigPlugin;
+impl Plugin for StyleTagConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let schedule = RichTextSchedule::get(app);
+        app.init_asset::<StyleTagConfig>()
+            .init_asset_loader::<StyleTagConfigLoader>()
+            .add_systems(schedule, sync_style_tag_configs.before(RichTextSystems));
+    }
+}
+
+/// A set of style tag definitions, keyed by tag.
+#[derive(Asset, TypePath, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StyleTagConfig {
+    /// The style tag definitions.
+    pub tags: HashMap<String, StyleTagDefinition>,
+}
+
+/// The components of a single style tag in a [`StyleTagConfig`].
+///
+/// Fields that are `None` are left out of the style entirely, so they don't
+/// override other tags.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StyleTagDefinition {
+    /// Adds a `TextColor` to the style. Written as a hex string like
+    /// `"#ff0000"`.
+    #[serde(with = "hex_color")]
+    pub color: Option<Color>,
+    /// Adds a `TextFont` with this font size to the style.
+    pub font_size: Option<f32>,
+    /// Adds a `TextFont` with the font at this asset path to the style.
+    pub font_path: Option<String>,
+}
+
+/// (De)serializes an optional color as a hex string like `"#ff0000"`.
+mod hex_color {
+    use bevy::color::{Color, Srgba};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match color {
+            Some(color) => serializer.serialize_some(&Srgba::from(*color).to_hex()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        let Some(hex) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        Srgba::hex(&hex)
+            .map(|color| Some(color.into()))
+            .map_err(|_| D::Error::custom(format!("`{hex}` is not a valid hex color")))
+    }
+}
+
+/// An error produced when loading a [`StyleTagConfig`].
+#[derive(Debug)]
+pub enum StyleTagConfigError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The file isn't valid RON.
+    Ron(ron::error::SpannedError),
+}
+impl fmt::Display for StyleTagConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read style tag config: {error}"),
+            Self::Ron(error) => write!(f, "could not parse style tag config: {error}"),
+        }
+    }
+}
+impl std::error::Error for StyleTagConfigError {}
+impl From<std::io::Error> for StyleTagConfigError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+impl From<ron::error::SpannedError> for StyleTagConfigError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+/// An [`AssetLoader`] for [`StyleTagConfig`]s in `.richstyle` and
+/// `.richtags.ron` files.
+#[derive(Default)]
+pub struct StyleTagConfigLoader;
+impl AssetLoader for StyleTagConfigLoader {
+    type Asset = StyleTagConfig;
+    type Settings = ();
+    type Error = StyleTagConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["richtags.ron", "richstyle"]
+    }
+}
+
+/// A component marking a style entity that was spawned from a
+/// [`StyleTagConfig`].
+#[derive(Component)]
+struct StyleTagConfigSource {
+    asset: AssetId<StyleTagConfig>,
+    tag: String,
+}
+
+/// Spawns, updates and despawns the style entities of [`StyleTagConfig`]s as
+/// they are loaded, modified and unloaded.
+fn sync_style_tag_configs(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<StyleTagConfig>>,
+    configs: Res<Assets<StyleTagConfig>>,
+    asset_server: Res<AssetServer>,
+    sources: Query<(Entity, &StyleTagConfigSource)>,
+    mut users: ResMut<StyleTagUsers>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id }
+        | AssetEvent::Modified { id }
+        | AssetEvent::Removed { id }
+        | AssetEvent::Unused { id }) = *event
+        else {
+            continue;
+        };
+
+        let tags = match event {
+            AssetEvent::Added { .. } | AssetEvent::Modified { .. } => {
+                configs.get(id).map(|config| &config.tags)
+            }
+            _ => None,
+        };
+
+        let mut existing = HashMap::new();
+        for (ent, source) in &sources {
+            if source.asset != id {
+                continue;
+            }
+
+            if tags.is_some_and(|tags| tags.contains_key(&source.tag)) {
+                existing.insert(source.tag.as_str(), ent);
+            } else {
+                commands.entity(ent).despawn();
+            }
+        }
+
+        for (tag, definition) in tags.into_iter().flatten() {
+            let mut style = match existing.get(tag.as_str()) {
+                Some(&ent) => {
+                    // Styles updated in place don't change the registry
+                    // itself, but rich text using them still needs to be
+                    // restyled.
+                    users.mark_changed(tag.clone());
+
+                    let mut style = commands.entity(ent);
+                    style.remove::<(TextColor, TextFont)>();
+                    style
+                }
+                None => commands.spawn((
+                    StyleTag::new(tag),
+                    StyleTagConfigSource {
+                        asset: id,
+                        tag: tag.clone(),
+                    },
+                )),
+            };
+
+            if let Some(color) = definition.color {
+                style.insert(TextColor(color));
+            }
+
+            if definition.font_size.is_some() || definition.font_path.is_some() {
+                let mut font = TextFont::default();
+                if let Some(font_size) = definition.font_size {
+                    font.font_size = font_size;
+                }
+                if let Some(path) = &definition.font_path {
+                    font.font = asset_server.load(path);
+                }
+                style.insert(font);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_style_tag_config() {
+    let config: StyleTagConfig = ron::de::from_str(
+        r##"(
+            tags: {
+                "red": (color: Some("#ff0000")),
+                "lg": (font_size: Some(40.0)),
+            },
+        )"##,
+    )
+    .unwrap();
+
+    assert_eq!(config.tags.len(), 2);
+    assert_eq!(config.tags["red"].color, Some(Color::srgb(1., 0., 0.)));
+    assert_eq!(config.tags["lg"].font_size, Some(40.));
+    assert_eq!(config.tags["lg"].font_path, None);
+}
+
+#[test]
+fn test_style_tag_config_hex() {
+    use bevy::color::Srgba;
+
+    let config: StyleTagConfig = ron::de::from_str(
+        r##"(
+            tags: {
+                "fancy": (color: Some("#ffd700"), font_path: Some("fonts/FiraMono-Medium.ttf"), font_size: Some(30.0)),
+                "plain": (),
+            },
+        )"##,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.tags["fancy"].color,
+        Some(Srgba::hex("ffd700").unwrap().into())
+    );
+    assert_eq!(config.tags["plain"].color, None);
+
+    assert!(StyleTagConfigLoader.extensions().contains(&"richstyle"));
+
+    // Colors are written back as hex.
+    let ron = ron::ser::to_string(&config).unwrap();
+    assert!(ron.contains("\"#FFD700\""));
+
+    assert!(
+        ron::de::from_str::<StyleTagConfig>(r##"(tags: { "bad": (color: Some("#nope")) })"##)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_style_tag_config_reload() {
+    use bevy::{asset::AssetPlugin, ecs::system::RunSystemOnce, prelude::Children};
+
+    use crate::{RichText, RichTextPlugin};
+
+    let mut app = App::new();
+    app.add_plugins((
+        bevy::core::TaskPoolPlugin::default(),
+        AssetPlugin::default(),
+        RichTextPlugin::default(),
+        StyleTagConfigPlugin,
+    ))
+    .init_asset::<bevy::text::Font>()
+    .register_type::<TextColor>();
+
+    let mut config = StyleTagConfig::default();
+    config.tags.insert(
+        "red".to_string(),
+        StyleTagDefinition {
+            color: Some(Color::srgb(1., 0., 0.)),
+            ..Default::default()
+        },
+    );
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<StyleTagConfig>>()
+        .add(config);
+
+    let ent = app.world_mut().spawn(RichText::new("[red]a")).id();
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let span_color = |app: &mut App| {
+        app.world_mut()
+            .run_system_once(
+                move |children: Query<&Children>, colors: Query<&TextColor>| {
+                    colors.get(children.get(ent).unwrap()[0]).unwrap().0
+                },
+            )
+            .unwrap()
+    };
+    assert_eq!(span_color(&mut app), Color::srgb(1., 0., 0.));
+
+    let style_ent = app
+        .world()
+        .resource::<crate::StyleTags>()
+        .get_or_none("red")
+        .unwrap();
+
+    app.world_mut()
+        .resource_mut::<Assets<StyleTagConfig>>()
+        .get_mut(&handle)
+        .unwrap()
+        .tags
+        .get_mut("red")
+        .unwrap()
+        .color = Some(Color::srgb(0., 0., 1.));
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(span_color(&mut app), Color::srgb(0., 0., 1.));
+    assert_eq!(
+        app.world()
+            .resource::<crate::StyleTags>()
+            .get_or_none("red"),
+        Some(style_ent)
+    );
+}
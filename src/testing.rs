@@ -0,0 +1,45 @@
+//! Helpers for testing code that produces rich text markup.
+//!
+//! Requires the `testing` feature.
+
+pub use crate::assert_rich_text_sections;
+
+/// Asserts that `markup` parses into exactly the given sections, where each
+/// section is a `(value, [tags])` tuple.
+///
+/// ```
+/// # use bevy_simple_rich_text::assert_rich_text_sections;
+/// assert_rich_text_sections!(
+///     "Hello [red,bold]World",
+///     [("Hello ", []), ("World", ["red", "bold"])]
+/// );
+/// ```
+///
+/// On failure, the number of sections and each mismatched section are
+/// reported along with the markup.
+#[macro_export]
+macro_rules! assert_rich_text_sections {
+    ($markup:expr, [$(($value:expr, [$($tag:expr),* $(,)?])),* $(,)?] $(,)?) => {{
+        let markup = $markup;
+        let actual = $crate::parse_richtext(markup)
+            .into_iter()
+            .map(|section| (section.value, section.tags))
+            .collect::<::std::vec::Vec<_>>();
+        let expected: ::std::vec::Vec<(::std::string::String, ::std::vec::Vec<::std::string::String>)> =
+            ::std::vec![$((
+                ::std::string::String::from($value),
+                ::std::vec![$(::std::string::String::from($tag)),*],
+            )),*];
+
+        ::std::assert_eq!(
+            actual.len(),
+            expected.len(),
+            "wrong number of sections for {:?}: {:?}",
+            markup,
+            actual
+        );
+        for (index, (actual, expected)) in actual.iter().zip(&expected).enumerate() {
+            ::std::assert_eq!(actual, expected, "section {} of {:?}", index, markup);
+        }
+    }};
+}
@@ -0,0 +1,172 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{Event, EventWriter},
+        system::{Commands, Local, Query, Res},
+    },
+    hierarchy::Children,
+    text::TextSpan,
+    time::Time,
+    utils::HashMap,
+};
+
+#[cfg(doc)]
+use crate::{RichText, RichText2d};
+
+/// A component that limits the number of visible characters of a [`RichText`]
+/// or [`RichText2d`].
+///
+/// Add [`TypewriterSpeed`] to reveal the text over time, or modify this
+/// directly.
+///
+/// `bevy_ui` doesn't support hiding individual spans, so the text of any span
+/// past the limit is truncated rather than hidden. The full text is restored
+/// when the limit increases.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaxVisibleChars(pub usize);
+
+/// A component that increases [`MaxVisibleChars`] by this many characters per
+/// second, until all of the text is visible.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct TypewriterSpeed(pub f32);
+
+/// An event sent when [`TypewriterSpeed`] has revealed all of the text of a
+/// [`RichText`] or [`RichText2d`].
+#[derive(Event, Debug, Clone)]
+pub struct TypewriterFinished {
+    /// The entity holding the rich text.
+    pub entity: Entity,
+}
+
+/// A component added to each `TextSpan` of a [`RichText`] or [`RichText2d`],
+/// holding the range of characters that the span occupies in the rendered
+/// text.
+///
+/// Offsets are in `char`s, not bytes, and `end` is exclusive.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanCharRange {
+    /// The offset of the first character of the span.
+    pub start: usize,
+    /// The offset just past the last character of the span.
+    pub end: usize,
+}
+
+/// The full text of a span that is being revealed by [`MaxVisibleChars`].
+#[derive(Component)]
+pub(crate) struct FullSpanText(String);
+
+pub(crate) fn typewriter_advance(
+    mut commands: Commands,
+    time: Option<Res<Time>>,
+    mut progress: Local<HashMap<Entity, f32>>,
+    mut rt_query: Query<(Entity, &mut MaxVisibleChars, Option<&TypewriterSpeed>)>,
+    children_query: Query<&Children>,
+    mut span_query: Query<(&SpanCharRange, &mut TextSpan, Option<&FullSpanText>)>,
+    mut finished: EventWriter<TypewriterFinished>,
+) {
+    progress.retain(|ent, _| rt_query.contains(*ent));
+
+    for (ent, mut max_visible, speed) in &mut rt_query {
+        let children = children_query
+            .get(ent)
+            .map(|children| &**children)
+            .unwrap_or_default();
+
+        let total = children
+            .iter()
+            .filter_map(|child| span_query.get(*child).ok())
+            .map(|(range, ..)| range.end)
+            .max()
+            .unwrap_or_default();
+
+        if let (Some(speed), Some(time)) = (speed, &time) {
+            if max_visible.0 < total {
+                let progress = progress.entry(ent).or_default();
+                *progress += speed.0 * time.delta_secs();
+
+                let advance = progress.floor();
+                *progress -= advance;
+
+                max_visible.0 = (max_visible.0 + advance as usize).min(total);
+                if max_visible.0 == total {
+                    finished.send(TypewriterFinished { entity: ent });
+                }
+            }
+        }
+
+        for child in children {
+            let Ok((range, mut span, full)) = span_query.get_mut(*child) else {
+                continue;
+            };
+
+            let full = match full {
+                Some(full) => full.0.clone(),
+                None => {
+                    commands.entity(*child).insert(FullSpanText(span.0.clone()));
+                    span.0.clone()
+                }
+            };
+
+            let visible = max_visible.0.saturating_sub(range.start);
+            let text = match full.char_indices().nth(visible) {
+                Some((index, _)) => &full[..index],
+                None => &full,
+            };
+
+            if span.0 != text {
+                span.0 = text.to_string();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_typewriter() {
+    use std::time::Duration;
+
+    use bevy::{app::App, ecs::event::Events, time::TimePlugin};
+
+    use crate::{RichText, RichTextPlugin};
+
+    let mut app = App::new();
+    app.add_plugins((TimePlugin, RichTextPlugin::default()));
+
+    let ent = app
+        .world_mut()
+        .spawn((
+            RichText::new("ab[red]cd"),
+            MaxVisibleChars(0),
+            TypewriterSpeed(3.),
+        ))
+        .id();
+    app.update();
+
+    let span_text = |app: &App| {
+        let world = app.world();
+        world
+            .get::<Children>(ent)
+            .unwrap()
+            .iter()
+            .map(|child| world.get::<TextSpan>(*child).unwrap().0.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(span_text(&app), vec!["", ""]);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs(1));
+    app.world_mut().run_schedule(bevy::app::Update);
+    assert_eq!(app.world().get::<MaxVisibleChars>(ent).unwrap().0, 3);
+    assert_eq!(span_text(&app), vec!["ab", "c"]);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs(1));
+    app.world_mut().run_schedule(bevy::app::Update);
+    assert_eq!(app.world().get::<MaxVisibleChars>(ent).unwrap().0, 4);
+    assert_eq!(span_text(&app), vec!["ab", "cd"]);
+
+    let events = app.world().resource::<Events<TypewriterFinished>>();
+    assert_eq!(events.len(), 1);
+}
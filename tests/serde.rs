@@ -0,0 +1,32 @@
+//! Tests for the `serde` feature.
+
+#![cfg(feature = "serde")]
+
+use bevy_simple_rich_text::{RichText, RichText2d, TextSection};
+
+#[test]
+fn test_richtext_round_trip() {
+    let json = serde_json::to_string(&RichText::new("[red]Hello[] World")).unwrap();
+    assert_eq!(json, r#""[red]Hello[] World""#);
+
+    let rt: RichText = serde_json::from_str(&json).unwrap();
+    assert_eq!(rt.0, "[red]Hello[] World");
+
+    let rt: RichText2d = serde_json::from_str(&json).unwrap();
+    assert_eq!(rt.0, "[red]Hello[] World");
+}
+
+#[test]
+fn test_text_section_round_trip() {
+    let section = TextSection {
+        value: "Hello".to_string(),
+        tags: vec!["red".to_string()],
+    };
+
+    let json = serde_json::to_string(&section).unwrap();
+    assert_eq!(json, r#"{"value":"Hello","tags":["red"]}"#);
+
+    let section: TextSection = serde_json::from_str(&json).unwrap();
+    assert_eq!(section.value, "Hello");
+    assert_eq!(section.tags, vec!["red".to_string()]);
+}